@@ -0,0 +1,249 @@
+use std::collections::VecDeque;
+
+use console_utils::input::select;
+
+use crate::{
+    game::Entity,
+    messages::{msg, text, Msg},
+    set_delay,
+};
+
+/// Everything which is able to choose between options, e.g. the next action of a player.
+///
+/// This decouples the fight from the terminal, so fights can also be run headless.
+pub trait ActionSource {
+    /// Chooses one of the `options` and returns its index.
+    fn choose(&mut self, prompt: &str, options: &[&str]) -> usize;
+
+    /// Chooses the next action of `entity` out of the `options` and returns its index.
+    /// Defaults to `choose` with the action prompt.
+    fn choose_action(&mut self, _entity: &Entity, options: &[&str]) -> usize {
+        self.choose(text(Msg::SelectAction), options)
+    }
+
+    /// Called if the last chosen action couldn't be done (e.g. not enough mana), before choosing again.
+    /// Defaults to doing nothing.
+    fn reject(&mut self) {}
+}
+
+/// Lets the user choose via the terminal. This is the default.
+#[derive(Debug, Default)]
+pub struct TerminalActions;
+
+impl ActionSource for TerminalActions {
+    fn choose(&mut self, prompt: &str, options: &[&str]) -> usize {
+        select(prompt, options)
+    }
+}
+
+/// Checks a recorded choice against the `options`, as playing on with other choices would be a different game.
+/// Panics if the index is out of bounds, so the recording doesn't match the config (or was edited)!
+fn check_recorded(i: usize, options: &[&str]) -> usize {
+    assert!(
+        i < options.len(),
+        "{}",
+        msg(Msg::ReplayMismatch, &[&i, &options.len()])
+    );
+    i
+}
+
+/// Returns a preset queue of indices, one per choice.
+#[derive(Debug, Default)]
+pub struct ScriptedActions {
+    indices: VecDeque<usize>,
+}
+
+impl ScriptedActions {
+    pub fn new(indices: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            indices: indices.into_iter().collect(),
+        }
+    }
+}
+
+impl ActionSource for ScriptedActions {
+    /// Panics if the script ran out of indices or the index is out of bounds of `options`,
+    /// see `check_recorded`!
+    fn choose(&mut self, _prompt: &str, options: &[&str]) -> usize {
+        let i = self
+            .indices
+            .pop_front()
+            .unwrap_or_else(|| panic!("{}", text(Msg::ReplayExhausted)));
+        check_recorded(i, options)
+    }
+}
+
+/// Returns a preset queue of indices first and then continues with another source, e.g. for a resumed game.
+///
+/// The recorded choices are usually run without the typing animation,
+/// so once they ran out the reveal delay is set back to `delay`.
+pub struct ResumedActions {
+    indices: VecDeque<usize>,
+    then: Box<dyn ActionSource>,
+    /// The delay to set back to, `None` once it was set back.
+    delay: Option<f64>,
+    /// Whether the last choice was a recorded one.
+    scripted: bool,
+}
+
+impl ResumedActions {
+    pub fn new(
+        indices: impl IntoIterator<Item = usize>,
+        then: Box<dyn ActionSource>,
+        delay: f64,
+    ) -> Self {
+        Self {
+            indices: indices.into_iter().collect(),
+            then,
+            delay: Some(delay),
+            scripted: false,
+        }
+    }
+
+    /// Pops the next recorded index, sets the delay back if there is none left.
+    fn next(&mut self) -> Option<usize> {
+        let i = self.indices.pop_front();
+        if let (None, Some(delay)) = (i, self.delay) {
+            set_delay(delay);
+            self.delay = None;
+        }
+        self.scripted = i.is_some();
+        i
+    }
+}
+
+/// Panics if a recorded index is out of bounds of the `options`, see `check_recorded`!
+impl ActionSource for ResumedActions {
+    fn choose(&mut self, prompt: &str, options: &[&str]) -> usize {
+        match self.next() {
+            Some(i) => check_recorded(i, options),
+            None => self.then.choose(prompt, options),
+        }
+    }
+
+    fn choose_action(&mut self, entity: &Entity, options: &[&str]) -> usize {
+        match self.next() {
+            Some(i) => check_recorded(i, options),
+            None => self.then.choose_action(entity, options),
+        }
+    }
+
+    /// Rejections of recorded choices are recorded too, so only the other source is told.
+    fn reject(&mut self) {
+        if !self.scripted {
+            self.then.reject();
+        }
+    }
+}
+
+/// Divisor of the max life points, below which the `AutoPlayer` counts as low on life points.
+pub const AUTO_LOW_LIFE_DIVISOR: usize = 3;
+
+/// Chooses by a simple policy without any user input:
+/// Attacks unless low on life points, then heals, drinks a potion or flees (first one available).
+/// It doesn't keep fighting after the last monster (see `Msg::ContinuePrompt`), as it would never stop.
+#[derive(Debug, Default)]
+pub struct AutoPlayer {
+    /// The last chosen option.
+    last: usize,
+    /// Options which were rejected in the current turn.
+    rejected: Vec<usize>,
+    /// Whether the next choice is a retry after a rejection.
+    retrying: bool,
+}
+
+impl AutoPlayer {
+    /// Gets the index of the first option starting with `key`.
+    fn find(options: &[&str], key: Msg) -> Option<usize> {
+        options.iter().position(|o| o.starts_with(text(key)))
+    }
+}
+
+impl ActionSource for AutoPlayer {
+    /// Always chooses the first option, only declines to keep fighting.
+    fn choose(&mut self, prompt: &str, options: &[&str]) -> usize {
+        if prompt == text(Msg::ContinuePrompt) {
+            Self::find(options, Msg::No).unwrap_or_default()
+        } else {
+            0
+        }
+    }
+
+    fn choose_action(&mut self, entity: &Entity, options: &[&str]) -> usize {
+        if !std::mem::take(&mut self.retrying) {
+            self.rejected.clear();
+        }
+        let low = entity.life_points() * AUTO_LOW_LIFE_DIVISOR < entity.max_life_points();
+        let preferred: &[Msg] = if low {
+            &[Msg::Heal, Msg::Potion, Msg::Flee, Msg::Attack]
+        } else {
+            &[Msg::Attack]
+        };
+        self.last = preferred
+            .iter()
+            .filter_map(|key| Self::find(options, *key))
+            .find(|i| !self.rejected.contains(i))
+            .or_else(|| Self::find(options, Msg::Attack))
+            .unwrap_or_default();
+        self.last
+    }
+
+    fn reject(&mut self) {
+        self.rejected.push(self.last);
+        self.retrying = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Combatant, Difficulty, FightOutcome, Fighter, GameRules, Monster};
+
+    #[test]
+    fn auto_player_attacks_and_saves_itself_when_low() {
+        let options = [Msg::Attack, Msg::Defend, Msg::Potion, Msg::Flee].map(text);
+        let mut auto = AutoPlayer::default();
+        let mut hero = Entity::new("Hero".into(), 30, 0, 0, None, 0);
+        assert_eq!(auto.choose_action(&hero, &options), 0);
+        hero.apply_dmg(25);
+        assert_eq!(auto.choose_action(&hero, &options), 2);
+        // A rejected potion falls back to fleeing
+        auto.reject();
+        assert_eq!(auto.choose_action(&hero, &options), 3);
+        // Never keeps fighting after the last monster
+        let answers = [Msg::Yes, Msg::No].map(text);
+        assert_eq!(auto.choose(text(Msg::ContinuePrompt), &answers), 1);
+    }
+
+    #[test]
+    fn auto_fight_with_a_seed_is_deterministic() {
+        let fight = || {
+            let mut game_rules = GameRules::with_seed(Difficulty::Normal, 7);
+            game_rules.set_actions(AutoPlayer::default());
+            let mut player = Fighter::new(Entity::new("Hero".into(), 100, 8, 10, None, 0), 2);
+            let mut monster =
+                Monster::new(Entity::new("Orc".into(), 40, 4, 4, None, 0), vec![], vec![]);
+            (
+                player.fight(&mut monster, &mut game_rules),
+                player.entity.life_points(),
+            )
+        };
+        let (result, life_points) = fight();
+        assert_eq!(result.outcome, FightOutcome::PlayerWon);
+        assert_eq!((result, life_points), fight());
+    }
+
+    #[test]
+    #[should_panic(expected = "Optionen")]
+    fn scripted_index_out_of_bounds_panics() {
+        ScriptedActions::new([2]).choose("", &["a", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Keine Aktionen")]
+    fn exhausted_script_panics() {
+        let mut actions = ScriptedActions::new([0]);
+        actions.choose("", &["a"]);
+        actions.choose("", &["a"]);
+    }
+}
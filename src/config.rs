@@ -0,0 +1,1216 @@
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use console_utils::input::{input, Empty};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    delay,
+    game::*,
+    messages::{msg, text, Msg},
+    output::reveal,
+};
+
+/// The current version of the config format, see `Config::migrate`.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// The config struct holds general Config for Player and Enemy with saving/loading from a file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Config {
+    /// The version of the format, missing in files of the first version.
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub player: Players,
+    pub enemy: Enemies,
+}
+
+fn default_version() -> u32 {
+    CONFIG_VERSION
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            player: Players::default(),
+            enemy: Enemies::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn _new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the config from a json or toml file (see `Format`) if it exists, migrates it
+    /// to the current version (see `migrate`) and validates it.
+    /// Weapons the players can't wield yet are unequipped, see `Entity::unequip_locked_weapons`.
+    /// Otherwise a default config is created at `path`.
+    pub fn load_from_file(path: &PathBuf) -> Result<Config, ConfigError> {
+        if path.exists() {
+            let mut config = Self::validate_file(path)?;
+            for member in config.player.members_mut() {
+                member.entity_mut().init_max_life_points();
+                member.entity_mut().unequip_locked_weapons();
+            }
+            for monster in config.enemy.monsters_mut() {
+                monster.entity.init_max_life_points();
+            }
+            reveal(&msg(Msg::ConfigLoaded, &[&format!("{path:?}")]), delay());
+            Ok(config)
+        } else {
+            reveal(&msg(Msg::ConfigCreated, &[&format!("{path:?}")]), delay());
+            let mut config = Config::default();
+            // The new player gets a name and distributes the points (see `PointBuy`)
+            let name = loop {
+                let name: String = input(text(Msg::NamePrompt));
+                if !name.trim().is_empty() {
+                    break name.trim().to_string();
+                }
+            };
+            *config.player.members_mut()[0].entity_mut() = PointBuy::prompt().entity(name);
+            Ok(Self::save_to_file(config, path)?)
+        }
+    }
+
+    /// Loads the config from a json or toml file (see `Format`), migrates it (see `migrate`)
+    /// and checks it with `validate` and `validate_bounds`, without any output.
+    /// Unlike `load_from_file` a missing file is an error instead of creating a default config.
+    pub fn validate_file(path: &Path) -> Result<Config, ConfigError> {
+        let mut value: serde_json::Value = match Format::from_path(path) {
+            Format::Json => {
+                let file = File::open(path)?;
+                let reader = BufReader::new(file);
+                serde_json::from_reader(reader)?
+            }
+            Format::Toml => toml::from_str(&fs::read_to_string(path)?)?,
+        };
+        Self::migrate(&mut value)?;
+        let mut config: Self = serde_json::from_value(value)?;
+        for member in config.player.members_mut() {
+            member.entity_mut().trim_name();
+        }
+        for monster in config.enemy.monsters_mut() {
+            monster.entity.trim_name();
+        }
+        config.validate()?;
+        config.validate_bounds(&StatBounds::default())?;
+        Ok(config)
+    }
+
+    /// Upgrades the config `value` of an older version (`1` if it has none) to `CONFIG_VERSION` step by step.
+    /// Returns `ConfigError::Version` if the version is newer than supported
+    /// and `ConfigError::Invalid` if the `value` isn't an object at all.
+    ///
+    /// - 1 to 2: Fills in the fields added since the first version with their defaults,
+    ///   for the player classes, the monsters and their entities.
+    pub fn migrate(value: &mut serde_json::Value) -> Result<(), ConfigError> {
+        let Some(value) = value.as_object_mut() else {
+            return Err(ConfigError::Invalid(text(Msg::NotAnObject).into()));
+        };
+        let version = match value.get("version") {
+            Some(version) => serde_json::from_value(version.clone())?,
+            None => 1,
+        };
+        if version > CONFIG_VERSION {
+            return Err(ConfigError::Version(version));
+        }
+        for from in version..CONFIG_VERSION {
+            if from == 1 {
+                migrate_v1(value)?;
+            }
+        }
+        value.insert("version".into(), CONFIG_VERSION.into());
+        Ok(())
+    }
+
+    /// Checks the config for values which would break a fight or produce confusing messages.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let members = self.player.members();
+        if members.is_empty() {
+            return Err(ConfigError::Invalid(text(Msg::NoPlayers).into()));
+        }
+        let monsters = self.enemy.monsters();
+        if monsters.is_empty() {
+            return Err(ConfigError::Invalid(text(Msg::NoEnemies).into()));
+        }
+        for entity in members
+            .iter()
+            .map(|m| m.entity())
+            .chain(monsters.iter().map(|m| &m.entity))
+        {
+            if entity.name().trim().is_empty() {
+                return Err(ConfigError::Invalid(text(Msg::EmptyName).into()));
+            }
+            if entity.life_points() == 0 {
+                return Err(ConfigError::Invalid(msg(
+                    Msg::ZeroLifePoints,
+                    &[&entity.name()],
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no stat exceeds the `bounds`, as absurd values would break the fight math.
+    /// Covers the entities with their weapons, the stats of the player classes and of the monsters.
+    pub fn validate_bounds(&self, bounds: &StatBounds) -> Result<(), ConfigError> {
+        let members = self
+            .player
+            .members()
+            .iter()
+            .map(|m| (m.entity(), m.numeric_stats()));
+        let monsters = self
+            .enemy
+            .monsters()
+            .iter()
+            .map(|m| (&m.entity, m.numeric_stats()));
+        for (entity, own_stats) in members.chain(monsters) {
+            let weapon_stats = entity.weapons().flat_map(|weapon| weapon.numeric_stats());
+            let inventory = ("inventory", entity.inventory().len());
+            for (field, value) in entity
+                .numeric_stats()
+                .into_iter()
+                .chain(weapon_stats)
+                .chain([inventory])
+                .chain(own_stats)
+            {
+                let max = match field {
+                    "life_points" | "max_life_points" => bounds.life_points,
+                    "modifier" | "spell_power" | "poison" => bounds.weapon,
+                    "potions" | "antidotes" | "inventory" => bounds.items,
+                    "level" => bounds.level,
+                    "phases" => bounds.percent,
+                    _ => bounds.stat,
+                };
+                if value > max {
+                    return Err(ConfigError::Invalid(msg(
+                        Msg::StatTooHigh,
+                        &[&entity.name(), &field, &value, &max],
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the current config to a json or toml file (see `Format`), creating missing parent directories
+    pub fn save_to_file(config: Config, path: &PathBuf) -> Result<Config, ConfigError> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        match Format::from_path(path) {
+            Format::Json => {
+                let file = File::create(path)?;
+                let writer = BufWriter::new(file);
+                serde_json::to_writer_pretty(writer, &config)?;
+            }
+            Format::Toml => fs::write(path, toml::to_string_pretty(&config)?)?,
+        }
+        Ok(config)
+    }
+}
+
+/// Migrates a config of the first version, which had a single player and a single monster, see `Config::migrate`.
+fn migrate_v1(value: &mut serde_json::Map<String, serde_json::Value>) -> Result<(), ConfigError> {
+    let player = value
+        .get_mut("player")
+        .and_then(|p| p.as_object_mut())
+        .into_iter()
+        .flat_map(|p| p.iter_mut());
+    for (class, player) in player {
+        let defaults = match class.as_str() {
+            "Fighter" => serde_json::to_value(Fighter::default())?,
+            "Mage" => serde_json::to_value(Mage::default())?,
+            "Archer" => serde_json::to_value(Archer::default())?,
+            "Rogue" => serde_json::to_value(Rogue::default())?,
+            _ => continue,
+        };
+        fill_defaults(player, defaults);
+    }
+    if let Some(enemy) = value.get_mut("enemy") {
+        fill_defaults(enemy, serde_json::to_value(Monster::default())?);
+    }
+    Ok(())
+}
+
+/// Inserts the fields of `defaults` missing in `value`, the `entity` of both recursively.
+fn fill_defaults(value: &mut serde_json::Value, defaults: serde_json::Value) {
+    let (Some(value), serde_json::Value::Object(defaults)) = (value.as_object_mut(), defaults)
+    else {
+        return;
+    };
+    for (field, default) in defaults {
+        match value.get_mut(&field) {
+            Some(entity) if field == "entity" => fill_defaults(entity, default),
+            Some(_) => {}
+            None => {
+                value.insert(field, default);
+            }
+        }
+    }
+}
+
+/// Upper bounds of the stats of a config, see `Config::validate_bounds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatBounds {
+    /// Bound of the (max) life points.
+    pub life_points: usize,
+    /// Bound of the weapon stats: The material modifier, the spell power and the poison.
+    pub weapon: usize,
+    /// Bound of every other stat, e.g. the strength, the experience or the stats of the classes.
+    pub stat: usize,
+    /// Bound of the count of items: The potions, the antidotes and the spare weapons of the inventory.
+    pub items: usize,
+    /// Bound of the level.
+    pub level: usize,
+    /// Bound of the stats in percent: The thresholds of the monster phases.
+    pub percent: usize,
+}
+
+impl Default for StatBounds {
+    fn default() -> Self {
+        Self {
+            life_points: 1_000_000,
+            weapon: 10_000,
+            stat: 10_000,
+            items: 99,
+            level: 100,
+            percent: 100,
+        }
+    }
+}
+
+/// Count of points a new player distributes, see `PointBuy`.
+pub const POINT_BUDGET: usize = 30;
+
+/// Life points gained by each point spent on them.
+pub const LIFE_POINTS_PER_POINT: usize = 5;
+
+/// The points a new player distributes across their stats, at most `POINT_BUDGET` in total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointBuy {
+    pub strength: usize,
+    pub dexterity: usize,
+    /// Each point gives `LIFE_POINTS_PER_POINT` life points.
+    pub life_points: usize,
+}
+
+impl PointBuy {
+    /// Prompts the user for the points until they are valid (see `validate`).
+    pub fn prompt() -> Self {
+        let points = |key| loop {
+            if let Empty::Some(n) = input::<Empty<usize>>(text(key)) {
+                break n;
+            }
+        };
+        loop {
+            reveal(
+                &msg(Msg::PointBuyIntro, &[&POINT_BUDGET, &LIFE_POINTS_PER_POINT]),
+                delay(),
+            );
+            let point_buy = Self {
+                strength: points(Msg::StrengthPrompt),
+                dexterity: points(Msg::DexterityPrompt),
+                life_points: points(Msg::LifePointsPrompt),
+            };
+            match point_buy.validate() {
+                Ok(()) => break point_buy,
+                Err(e) => reveal(&format!("{e}\n"), delay()),
+            }
+        }
+    }
+
+    /// Gets the count of distributed points.
+    pub fn total(&self) -> usize {
+        self.strength
+            .saturating_add(self.dexterity)
+            .saturating_add(self.life_points)
+    }
+
+    /// Checks that the points don't exceed `POINT_BUDGET` and at least one is spent on life points.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.total() > POINT_BUDGET {
+            return Err(ConfigError::Invalid(msg(
+                Msg::PointsOverBudget,
+                &[&self.total(), &POINT_BUDGET],
+            )));
+        }
+        if self.life_points == 0 {
+            return Err(ConfigError::Invalid(text(Msg::NoLifePointsBought).into()));
+        }
+        Ok(())
+    }
+
+    /// Creates the entity called `name` with the bought stats.
+    pub fn entity(&self, name: String) -> Entity {
+        Entity::new(
+            name,
+            self.life_points.saturating_mul(LIFE_POINTS_PER_POINT),
+            self.dexterity,
+            self.strength,
+            None,
+            0,
+        )
+    }
+}
+
+/// Gets a JSON Schema of the config format, so editors can validate config files.
+///
+/// It's written by hand, so it has to be updated with the config structs.
+pub fn schema() -> serde_json::Value {
+    let count = || json!({ "type": "integer", "minimum": 0 });
+    let class = |extra: serde_json::Value| {
+        let mut properties = json!({ "entity": { "$ref": "#/$defs/Entity" } });
+        properties
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        json!({ "type": "object", "properties": properties, "required": ["entity"] })
+    };
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Config",
+        "type": "object",
+        "properties": {
+            "version": { "type": "integer", "minimum": 1, "maximum": CONFIG_VERSION },
+            "player": {
+                "oneOf": [
+                    { "$ref": "#/$defs/PlayerType" },
+                    { "type": "array", "items": { "$ref": "#/$defs/PlayerType" } }
+                ]
+            },
+            "enemy": {
+                "oneOf": [
+                    { "$ref": "#/$defs/Monster" },
+                    { "type": "array", "items": { "$ref": "#/$defs/Monster" } }
+                ]
+            }
+        },
+        "required": ["player", "enemy"],
+        "$defs": {
+            "PlayerType": {
+                "type": "object",
+                "properties": {
+                    "Fighter": class(json!({ "endurance": count() })),
+                    "Mage": class(json!({
+                        "magic_power": count(),
+                        "mana": count(),
+                        "max_mana": count(),
+                        "heal_cost": count(),
+                        "base_heal": count()
+                    })),
+                    "Archer": class(json!({ "accuracy": count() })),
+                    "Rogue": class(json!({ "stealth": count() }))
+                },
+                "minProperties": 1,
+                "maxProperties": 1
+            },
+            "Entity": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "life_points": count(),
+                    "max_life_points": count(),
+                    "dexterity": count(),
+                    "strength": count(),
+                    "weapon": { "oneOf": [{ "$ref": "#/$defs/Weapon" }, { "type": "null" }] },
+                    "off_hand": { "oneOf": [{ "$ref": "#/$defs/Weapon" }, { "type": "null" }] },
+                    "armor": count(),
+                    "experience": count(),
+                    "level": count(),
+                    "resistances": { "type": "array", "items": { "$ref": "#/$defs/Material" } },
+                    "potions": count(),
+                    "antidotes": count(),
+                    "regen": count(),
+                    "thorns": count(),
+                    "inventory": { "type": "array", "items": { "$ref": "#/$defs/Weapon" } },
+                    "physical_weakness": count(),
+                    "magic_weakness": count()
+                },
+                "required": ["name", "life_points", "dexterity", "strength"]
+            },
+            "Weapon": {
+                "type": "object",
+                "properties": {
+                    "material": { "$ref": "#/$defs/Material" },
+                    "spell_power": count(),
+                    "poison": count(),
+                    "durability": { "oneOf": [count(), { "type": "null" }] },
+                    "two_handed": { "type": "boolean" },
+                    "enchantment": {
+                        "oneOf": [
+                            { "enum": ["Fire", "Frost", "Vampiric"] },
+                            { "type": "null" }
+                        ]
+                    }
+                },
+                "required": ["material", "spell_power"]
+            },
+            "Material": {
+                "oneOf": [
+                    { "enum": ["Wood", "Stone", "Iron", "Gold", "MagicOre", "Diamond"] },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "Custom": {
+                                "type": "object",
+                                "properties": { "name": { "type": "string" }, "modifier": count() },
+                                "required": ["name", "modifier"]
+                            }
+                        },
+                        "required": ["Custom"]
+                    }
+                ]
+            },
+            "Monster": {
+                "type": "object",
+                "properties": {
+                    "entity": { "$ref": "#/$defs/Entity" },
+                    "ai": { "enum": ["Aggressive", "Defensive", "Fleeing"] },
+                    "level_scaling": count(),
+                    "phases": { "type": "array", "items": count() },
+                    "loot": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "weight": count(),
+                                "drop": {
+                                    "oneOf": [
+                                        { "const": "Nothing" },
+                                        {
+                                            "type": "object",
+                                            "properties": {
+                                                "Weapon": { "$ref": "#/$defs/Weapon" },
+                                                "Strength": count(),
+                                                "Dexterity": count()
+                                            },
+                                            "minProperties": 1,
+                                            "maxProperties": 1
+                                        }
+                                    ]
+                                }
+                            },
+                            "required": ["weight", "drop"]
+                        }
+                    }
+                },
+                "required": ["entity"]
+            }
+        }
+    })
+}
+
+/// The file format of a config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    /// Detects the format by the file extension, json if the extension is unknown.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// The players loaded from the file, either a single player or a party fighting together.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Players {
+    Single(Box<PlayerType>),
+    Party(Vec<PlayerType>),
+}
+
+impl PlayerType {
+    /// Gets the stats of the player class affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> Vec<(&'static str, usize)> {
+        match self {
+            Self::Fighter(fighter) => fighter.numeric_stats().to_vec(),
+            Self::Mage(mage) => mage.numeric_stats().to_vec(),
+            Self::Archer(archer) => archer.numeric_stats().to_vec(),
+            Self::Rogue(rogue) => rogue.numeric_stats().to_vec(),
+        }
+    }
+}
+
+impl Players {
+    /// Gets the players in turn order.
+    pub fn members(&self) -> &[PlayerType] {
+        match self {
+            Self::Single(player) => std::slice::from_ref(&**player),
+            Self::Party(members) => members,
+        }
+    }
+
+    /// Gets the players in turn order, mutable.
+    pub fn members_mut(&mut self) -> &mut [PlayerType] {
+        match self {
+            Self::Single(player) => std::slice::from_mut(&mut **player),
+            Self::Party(members) => members,
+        }
+    }
+}
+
+impl Default for Players {
+    fn default() -> Self {
+        Self::Single(Box::default())
+    }
+}
+
+/// The player type loaded from the file
+#[derive(Debug, Serialize, Deserialize)]
+pub enum PlayerType {
+    Fighter(Fighter),
+    Mage(Mage),
+    Archer(Archer),
+    Rogue(Rogue),
+}
+
+/// Forwards every overwritable method to the player class, so players of different classes can fight together.
+impl Combatant for PlayerType {
+    /// Gets a reference of the entity of the player.
+    fn entity(&self) -> &Entity {
+        match self {
+            Self::Fighter(fighter) => fighter.entity(),
+            Self::Mage(mage) => mage.entity(),
+            Self::Archer(archer) => archer.entity(),
+            Self::Rogue(rogue) => rogue.entity(),
+        }
+    }
+
+    /// Gets a mutable reference of the entity of the player.
+    fn entity_mut(&mut self) -> &mut Entity {
+        match self {
+            Self::Fighter(fighter) => fighter.entity_mut(),
+            Self::Mage(mage) => mage.entity_mut(),
+            Self::Archer(archer) => archer.entity_mut(),
+            Self::Rogue(rogue) => rogue.entity_mut(),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::Fighter(fighter) => fighter.name(),
+            Self::Mage(mage) => mage.name(),
+            Self::Archer(archer) => archer.name(),
+            Self::Rogue(rogue) => rogue.name(),
+        }
+    }
+
+    fn attack_damage(&self) -> usize {
+        match self {
+            Self::Fighter(fighter) => fighter.attack_damage(),
+            Self::Mage(mage) => mage.attack_damage(),
+            Self::Archer(archer) => archer.attack_damage(),
+            Self::Rogue(rogue) => rogue.attack_damage(),
+        }
+    }
+
+    fn weapon_attack_damage(&self) -> usize {
+        match self {
+            Self::Fighter(fighter) => fighter.weapon_attack_damage(),
+            Self::Mage(mage) => mage.weapon_attack_damage(),
+            Self::Archer(archer) => archer.weapon_attack_damage(),
+            Self::Rogue(rogue) => rogue.weapon_attack_damage(),
+        }
+    }
+
+    fn proficiency(&self) -> Proficiency {
+        match self {
+            Self::Fighter(fighter) => fighter.proficiency(),
+            Self::Mage(mage) => mage.proficiency(),
+            Self::Archer(archer) => archer.proficiency(),
+            Self::Rogue(rogue) => rogue.proficiency(),
+        }
+    }
+
+    fn damage_type(&self) -> DamageType {
+        match self {
+            Self::Fighter(fighter) => fighter.damage_type(),
+            Self::Mage(mage) => mage.damage_type(),
+            Self::Archer(archer) => archer.damage_type(),
+            Self::Rogue(rogue) => rogue.damage_type(),
+        }
+    }
+
+    fn on_hit(&mut self, dmg: usize) {
+        match self {
+            Self::Fighter(fighter) => fighter.on_hit(dmg),
+            Self::Mage(mage) => mage.on_hit(dmg),
+            Self::Archer(archer) => archer.on_hit(dmg),
+            Self::Rogue(rogue) => rogue.on_hit(dmg),
+        }
+    }
+
+    fn start_fight(&mut self) {
+        match self {
+            Self::Fighter(fighter) => fighter.start_fight(),
+            Self::Mage(mage) => mage.start_fight(),
+            Self::Archer(archer) => archer.start_fight(),
+            Self::Rogue(rogue) => rogue.start_fight(),
+        }
+    }
+
+    fn start_round(&mut self) {
+        match self {
+            Self::Fighter(fighter) => fighter.start_round(),
+            Self::Mage(mage) => mage.start_round(),
+            Self::Archer(archer) => archer.start_round(),
+            Self::Rogue(rogue) => rogue.start_round(),
+        }
+    }
+
+    fn can_stun(&self) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.can_stun(),
+            Self::Mage(mage) => mage.can_stun(),
+            Self::Archer(archer) => archer.can_stun(),
+            Self::Rogue(rogue) => rogue.can_stun(),
+        }
+    }
+
+    fn can_break_guard(&self) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.can_break_guard(),
+            Self::Mage(mage) => mage.can_break_guard(),
+            Self::Archer(archer) => archer.can_break_guard(),
+            Self::Rogue(rogue) => rogue.can_break_guard(),
+        }
+    }
+
+    fn can_taunt(&self) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.can_taunt(),
+            Self::Mage(mage) => mage.can_taunt(),
+            Self::Archer(archer) => archer.can_taunt(),
+            Self::Rogue(rogue) => rogue.can_taunt(),
+        }
+    }
+
+    fn first_strike(&self) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.first_strike(),
+            Self::Mage(mage) => mage.first_strike(),
+            Self::Archer(archer) => archer.first_strike(),
+            Self::Rogue(rogue) => rogue.first_strike(),
+        }
+    }
+
+    fn can_heal(&self) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.can_heal(),
+            Self::Mage(mage) => mage.can_heal(),
+            Self::Archer(archer) => archer.can_heal(),
+            Self::Rogue(rogue) => rogue.can_heal(),
+        }
+    }
+
+    fn heal_option(&self) -> String {
+        match self {
+            Self::Fighter(fighter) => fighter.heal_option(),
+            Self::Mage(mage) => mage.heal_option(),
+            Self::Archer(archer) => archer.heal_option(),
+            Self::Rogue(rogue) => rogue.heal_option(),
+        }
+    }
+
+    fn heal(&mut self, game_rules: &mut GameRules) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.heal(game_rules),
+            Self::Mage(mage) => mage.heal(game_rules),
+            Self::Archer(archer) => archer.heal(game_rules),
+            Self::Rogue(rogue) => rogue.heal(game_rules),
+        }
+    }
+
+    fn select_action<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool {
+        match self {
+            Self::Fighter(fighter) => fighter.select_action(enemy, game_rules),
+            Self::Mage(mage) => mage.select_action(enemy, game_rules),
+            Self::Archer(archer) => archer.select_action(enemy, game_rules),
+            Self::Rogue(rogue) => rogue.select_action(enemy, game_rules),
+        }
+    }
+}
+
+impl Default for PlayerType {
+    fn default() -> Self {
+        Self::Fighter(Fighter::default())
+    }
+}
+
+impl fmt::Display for PlayerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fighter(fighter) => fighter.fmt(f),
+            Self::Mage(mage) => mage.fmt(f),
+            Self::Archer(archer) => archer.fmt(f),
+            Self::Rogue(rogue) => rogue.fmt(f),
+        }
+    }
+}
+
+/// The enemies loaded from the file, either a single monster or a series of monsters (a dungeon run).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Enemies {
+    Single(Box<Monster>),
+    Dungeon(Vec<Monster>),
+}
+
+impl Enemies {
+    /// Gets the monsters in fighting order.
+    pub fn monsters(&self) -> &[Monster] {
+        match self {
+            Self::Single(monster) => std::slice::from_ref(&**monster),
+            Self::Dungeon(monsters) => monsters,
+        }
+    }
+
+    /// Gets the monsters in fighting order, mutable.
+    pub fn monsters_mut(&mut self) -> &mut [Monster] {
+        match self {
+            Self::Single(monster) => std::slice::from_mut(&mut **monster),
+            Self::Dungeon(monsters) => monsters,
+        }
+    }
+}
+
+impl Default for Enemies {
+    fn default() -> Self {
+        Self::Single(Box::default())
+    }
+}
+
+/// Errors while loading the config.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file couldn't be read or written.
+    Io(io::Error),
+    /// The file isn't valid json for a config.
+    Json(serde_json::Error),
+    /// The file isn't valid toml for a config.
+    TomlDe(toml::de::Error),
+    /// The config couldn't be written as toml.
+    TomlSer(toml::ser::Error),
+    /// The config has values which would break a fight.
+    Invalid(String),
+    /// The config has a version newer than `CONFIG_VERSION`, so it's from a newer game.
+    Version(u32),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", msg(Msg::ConfigIoError, &[e])),
+            Self::Json(e) => write!(f, "{}", msg(Msg::ConfigMalformed, &[e])),
+            Self::TomlDe(e) => write!(f, "{}", msg(Msg::ConfigMalformed, &[e])),
+            Self::TomlSer(e) => write!(f, "{}", msg(Msg::ConfigIoError, &[e])),
+            Self::Invalid(reason) => write!(f, "{}", msg(Msg::ConfigInvalid, &[reason])),
+            Self::Version(version) => write!(
+                f,
+                "{}",
+                msg(Msg::ConfigVersionTooNew, &[version, &CONFIG_VERSION])
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::TomlDe(e)
+    }
+}
+
+impl From<toml::ser::Error> for ConfigError {
+    fn from(e: toml::ser::Error) -> Self {
+        Self::TomlSer(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    /// A file in the temp dir, which is removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl std::ops::Deref for TempFile {
+        type Target = PathBuf;
+
+        fn deref(&self) -> &PathBuf {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// Writes `contents` to a file called `name` in the temp dir and returns it.
+    fn write_temp(name: &str, contents: &str) -> TempFile {
+        let path =
+            std::env::temp_dir().join(format!("simple-fantasy-game-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+
+    /// A config of a fighter called `Hero` with `life_points` against an orc.
+    fn config(life_points: usize) -> Value {
+        json!({
+            "player": { "Fighter": {
+                "entity": { "name": "Hero", "life_points": life_points, "dexterity": 5, "strength": 5 },
+                "endurance": 1
+            } },
+            "enemy": { "entity": { "name": "Orc", "life_points": 40, "dexterity": 5, "strength": 5 } }
+        })
+    }
+
+    #[test]
+    fn malformed_or_dead_configs_are_errors() {
+        let path = write_temp("malformed.json", r#"{"player": "#);
+        assert!(matches!(
+            Config::load_from_file(&path),
+            Err(ConfigError::Json(_))
+        ));
+
+        let path = write_temp("dead.json", &config(0).to_string());
+        match Config::load_from_file(&path) {
+            Err(ConfigError::Invalid(reason)) => {
+                assert_eq!(reason, msg(Msg::ZeroLifePoints, &[&"Hero"]));
+            }
+            other => panic!("Expected an invalid config, got {other:?}"),
+        }
+
+        let path = write_temp("alive.json", &config(10).to_string());
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.player.members()[0].entity().life_points(), 10);
+    }
+
+    #[test]
+    fn json_and_toml_configs_round_trip() {
+        let written = write_temp("written.json", &config(10).to_string());
+        for name in ["round-trip.json", "round-trip.toml"] {
+            let path = write_temp(name, "");
+            let config = Config::load_from_file(&written).unwrap();
+            let saved = serde_json::to_value(Config::save_to_file(config, &path).unwrap()).unwrap();
+            let loaded = serde_json::to_value(Config::load_from_file(&path).unwrap()).unwrap();
+            assert_eq!(loaded, saved, "{name}");
+        }
+        assert_eq!(Format::from_path(Path::new("a.TOML")), Format::Toml);
+    }
+
+    #[test]
+    fn missing_parent_directories_are_created() {
+        let dir =
+            std::env::temp_dir().join(format!("simple-fantasy-game-{}-dir", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("nested").join("config.json");
+        Config::save_to_file(Config::default(), &path).unwrap();
+        assert!(path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn schema_is_json_with_the_config_properties() {
+        let schema: Value = serde_json::from_str(&schema().to_string()).unwrap();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], json!(["player", "enemy"]));
+        for def in ["PlayerType", "Entity", "Weapon", "Material", "Monster"] {
+            assert!(schema["$defs"][def].is_object(), "{def} is missing");
+        }
+        // Every field of a serialized entity is described
+        let entity = serde_json::to_value(Entity::default()).unwrap();
+        for field in entity.as_object().unwrap().keys() {
+            assert!(
+                schema["$defs"]["Entity"]["properties"][field].is_object(),
+                "{field} is missing"
+            );
+        }
+    }
+
+    #[test]
+    fn first_version_config_is_migrated() {
+        // The format of the first version: Only a weapon with a material and a spell power,
+        // no armor, mana, levels or loot and no version at all
+        let v1 = json!({
+            "player": { "Mage": {
+                "entity": {
+                    "name": "Alice", "life_points": 100, "dexterity": 12, "strength": 30,
+                    "weapon": { "material": "Wood", "spell_power": 4 }
+                },
+                "magic_power": 2
+            } },
+            "enemy": { "entity": { "name": "Bob", "life_points": 100, "dexterity": 9, "strength": 50, "weapon": null } }
+        });
+        let mut migrated = v1.clone();
+        Config::migrate(&mut migrated).unwrap();
+        assert_eq!(migrated["version"], CONFIG_VERSION);
+        let mage = &migrated["player"]["Mage"];
+        assert_eq!(mage["max_mana"], DEFAULT_MAX_MANA);
+        assert_eq!(mage["entity"]["armor"], 0);
+        assert_eq!(mage["entity"]["name"], "Alice");
+        assert_eq!(migrated["enemy"]["level_scaling"], DEFAULT_LEVEL_SCALING);
+
+        let path = write_temp("v1.json", &v1.to_string());
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        let alice = config.player.members()[0].entity();
+        assert_eq!((alice.life_points(), alice.max_life_points()), (100, 100));
+        assert_eq!(alice.level(), 1);
+    }
+
+    #[test]
+    fn non_object_config_is_an_error() {
+        for contents in ["[]", "42", "\"Hero\""] {
+            let path = write_temp("non-object.json", contents);
+            match Config::load_from_file(&path) {
+                Err(ConfigError::Invalid(reason)) => assert_eq!(reason, text(Msg::NotAnObject)),
+                other => panic!("Expected an invalid config, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn newer_config_version_is_rejected() {
+        let mut value = config(10);
+        value["version"] = (CONFIG_VERSION + 1).into();
+        let path = write_temp("newer.json", &value.to_string());
+        let error = Config::load_from_file(&path).unwrap_err();
+        assert!(matches!(error, ConfigError::Version(v) if v == CONFIG_VERSION + 1));
+        assert_eq!(
+            error.to_string(),
+            msg(
+                Msg::ConfigVersionTooNew,
+                &[&(CONFIG_VERSION + 1), &CONFIG_VERSION]
+            )
+        );
+    }
+
+    #[test]
+    fn absurd_stats_are_rejected() {
+        let mut value = config(10);
+        value["player"]["Fighter"]["entity"]["strength"] = usize::MAX.into();
+        let path = write_temp("absurd.json", &value.to_string());
+        let max = StatBounds::default().stat;
+        match Config::load_from_file(&path) {
+            Err(ConfigError::Invalid(reason)) => assert_eq!(
+                reason,
+                msg(Msg::StatTooHigh, &[&"Hero", &"strength", &usize::MAX, &max])
+            ),
+            other => panic!("Expected an invalid config, got {other:?}"),
+        }
+
+        // The bounds are configurable and cover the weapons too
+        let mut value = config(10);
+        value["enemy"]["entity"]["weapon"] = json!({ "material": "Iron", "spell_power": 50 });
+        let armed: Config = serde_json::from_value(value).unwrap();
+        assert!(armed.validate_bounds(&StatBounds::default()).is_ok());
+        let bounds = StatBounds {
+            weapon: 20,
+            ..StatBounds::default()
+        };
+        match armed.validate_bounds(&bounds) {
+            Err(ConfigError::Invalid(reason)) => assert_eq!(
+                reason,
+                msg(Msg::StatTooHigh, &[&"Orc", &"spell_power", &50, &20])
+            ),
+            other => panic!("Expected an invalid config, got {other:?}"),
+        }
+
+        // The level, the class stats and the monster stats are bounded as well
+        let cases = [
+            (
+                "/player/Fighter/entity",
+                "level",
+                json!(101),
+                "Hero",
+                101,
+                100,
+            ),
+            (
+                "/player/Fighter",
+                "endurance",
+                json!(10_001),
+                "Hero",
+                10_001,
+                10_000,
+            ),
+            (
+                "/enemy",
+                "level_scaling",
+                json!(10_001),
+                "Orc",
+                10_001,
+                10_000,
+            ),
+            ("/enemy", "phases", json!([50, 150]), "Orc", 150, 100),
+        ];
+        for (parent, field, stat, name, value, max) in cases {
+            let mut absurd = config(10);
+            absurd.pointer_mut(parent).unwrap()[field] = stat;
+            let absurd: Config = serde_json::from_value(absurd).unwrap();
+            match absurd.validate_bounds(&StatBounds::default()) {
+                Err(ConfigError::Invalid(reason)) => assert_eq!(
+                    reason,
+                    msg(Msg::StatTooHigh, &[&name, &field, &value, &max])
+                ),
+                other => panic!("Expected an invalid {field}, got {other:?}"),
+            }
+        }
+
+        // The loot is bounded as well: The weights, the boosts and the stats of the dropped weapons
+        let weapon = json!({ "Weapon": { "material": "Iron", "spell_power": 10_001 } });
+        let loot = [
+            (
+                json!({ "weight": usize::MAX, "drop": "Nothing" }),
+                "weight",
+                usize::MAX,
+            ),
+            (
+                json!({ "weight": 1, "drop": { "Strength": 10_001 } }),
+                "Strength",
+                10_001,
+            ),
+            (
+                json!({ "weight": 1, "drop": weapon }),
+                "spell_power",
+                10_001,
+            ),
+        ];
+        for (entry, field, value) in loot {
+            let mut absurd = config(10);
+            absurd["enemy"]["loot"] = json!([entry]);
+            let absurd: Config = serde_json::from_value(absurd).unwrap();
+            match absurd.validate_bounds(&StatBounds::default()) {
+                Err(ConfigError::Invalid(reason)) => assert_eq!(
+                    reason,
+                    msg(Msg::StatTooHigh, &[&"Orc", &field, &value, &10_000])
+                ),
+                other => panic!("Expected an invalid {field}, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn starting_items_are_loaded_within_the_bounds() {
+        let mut value = config(10);
+        let entity = &mut value["player"]["Fighter"]["entity"];
+        entity["potions"] = 3.into();
+        entity["antidotes"] = 1.into();
+        entity["inventory"] = json!([
+            { "material": "Iron", "spell_power": 0 },
+            { "material": "Stone", "spell_power": 0, "two_handed": true }
+        ]);
+        let path = write_temp("items.json", &value.to_string());
+        let loaded = Config::load_from_file(&path).unwrap();
+        let hero = loaded.player.members()[0].entity();
+        assert_eq!((hero.potions(), hero.antidotes()), (3, 1));
+        assert_eq!(
+            hero.inventory(),
+            [
+                Weapon::new(Material::Iron, 0, 0, None, false, None),
+                Weapon::new(Material::Stone, 0, 0, None, true, None)
+            ]
+        );
+
+        // Without any items the defaults are used
+        let plain: Config = serde_json::from_value(config(10)).unwrap();
+        let hero = plain.player.members()[0].entity();
+        assert_eq!((hero.potions(), hero.inventory().len()), (0, 0));
+
+        value["player"]["Fighter"]["entity"]["potions"] = 1000.into();
+        let path = write_temp("too-many-items.json", &value.to_string());
+        let max = StatBounds::default().items;
+        match Config::load_from_file(&path) {
+            Err(ConfigError::Invalid(reason)) => assert_eq!(
+                reason,
+                msg(Msg::StatTooHigh, &[&"Hero", &"potions", &1000, &max])
+            ),
+            other => panic!("Expected an invalid config, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn names_are_trimmed_and_must_not_be_empty() {
+        let mut value = config(10);
+        value["player"]["Fighter"]["entity"]["name"] = "  Hero \t".into();
+        let path = write_temp("padded.json", &value.to_string());
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.player.members()[0].entity().name(), "Hero");
+
+        value["enemy"]["entity"]["name"] = "   ".into();
+        let path = write_temp("empty.json", &value.to_string());
+        match Config::load_from_file(&path) {
+            Err(ConfigError::Invalid(reason)) => assert_eq!(reason, text(Msg::EmptyName)),
+            other => panic!("Expected an invalid config, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn point_buy_rejects_over_budget_allocations() {
+        let point_buy = PointBuy {
+            strength: 10,
+            dexterity: 10,
+            life_points: 10,
+        };
+        assert!(point_buy.validate().is_ok());
+        let entity = point_buy.entity("Hero".into());
+        assert_eq!(entity.life_points(), 10 * LIFE_POINTS_PER_POINT);
+
+        let over_budget = PointBuy {
+            strength: 11,
+            ..point_buy
+        };
+        match over_budget.validate() {
+            Err(ConfigError::Invalid(reason)) => {
+                assert_eq!(reason, msg(Msg::PointsOverBudget, &[&31, &POINT_BUDGET]));
+            }
+            other => panic!("Expected an over budget allocation, got {other:?}"),
+        }
+        let overflowing = PointBuy {
+            strength: usize::MAX,
+            ..point_buy
+        };
+        assert!(overflowing.validate().is_err());
+
+        let no_life_points = PointBuy {
+            life_points: 0,
+            ..point_buy
+        };
+        assert!(no_life_points.validate().is_err());
+    }
+}
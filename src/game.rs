@@ -1,21 +1,221 @@
-use std::fmt::Debug;
+use std::{
+    cmp,
+    fmt::{self, Debug},
+    io,
+    ops::RangeInclusive,
+    path::PathBuf,
+    slice,
+    str::FromStr,
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
+};
 
-use console_utils::input::{reveal, select};
-use rand::{rngs::SmallRng, Rng, SeedableRng};
-use serde::{Deserialize, Serialize};
+use console_utils::input::{input, Empty};
+use rand::{rngs::SmallRng, SeedableRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::TIME_BETWEEN;
+use crate::{
+    action::{ActionSource, TerminalActions},
+    delay,
+    messages::{msg, text, Msg},
+    observer::{FightObserver, NoObserver},
+    output::reveal,
+    random::RandomSource,
+    replay::Replay,
+};
+
+/// Damage multiplier applied on a critical hit.
+pub const CRIT_MULTIPLIER: usize = 2;
+/// Experience needed per level to reach the next one.
+pub const XP_PER_LEVEL: usize = 100;
+/// Strength gained on a level up.
+pub const LEVEL_UP_STRENGTH: usize = 5;
+/// Life points (and max life points) gained on a level up.
+pub const LEVEL_UP_LIFE_POINTS: usize = 10;
+/// Minimum count of dice sides, less would break the flee and crit chances.
+pub const MIN_DICE_SIDES: usize = 2;
+/// Rolled dexterity a defender has to exceed to dodge an attack.
+pub const DODGE_THRESHOLD: usize = 15;
+/// Damage bonus of two-handed weapons.
+pub const TWO_HANDED_DAMAGE_BONUS: usize = 5;
+/// Dexterity penalty when wielding a two-handed weapon.
+pub const TWO_HANDED_DEXTERITY_PENALTY: usize = 5;
+/// Divisor of the damage of a counterattack.
+pub const COUNTER_DAMAGE_DIVISOR: usize = 2;
+/// Life points healed by a potion.
+pub const POTION_HEAL: usize = 25;
+/// Count of rounds a poison lasts.
+pub const POISON_TURNS: usize = 3;
+/// Rounds until fleeing is possible again after a failed flee, counting the round of the flee.
+pub const FLEE_COOLDOWN_ROUNDS: usize = 3;
+/// Percent the flee chance is reduced by for every unit of carried weight, see `Entity::flee_penalty`.
+pub const FLEE_WEIGHT_PENALTY_PERCENT: usize = 5;
+/// Maximum percent the flee chance is reduced by the carried weight, so fleeing always stays possible.
+pub const MAX_FLEE_WEIGHT_PENALTY: usize = 75;
+/// Strength gained by a boss monster for every phase after the first.
+pub const PHASE_STRENGTH_BONUS: usize = 5;
+/// Deviation of a rolled attack damage from the base damage in percent.
+pub const DAMAGE_SPREAD_PERCENT: usize = 25;
+/// Default count of rounds after which a fight ends in a draw.
+pub const DEFAULT_MAX_ROUNDS: usize = 100;
+/// Life point thresholds in percent of the max life points, below which a monster gets enraged.
+pub const ENRAGE_THRESHOLDS: [usize; 2] = [50, 25];
+/// Strength in percent a monster gains for every crossed `ENRAGE_THRESHOLDS`.
+pub const ENRAGE_STRENGTH_PERCENT: usize = 25;
+/// Count of characters of a health bar.
+pub const HEALTHBAR_WIDTH: usize = 10;
+/// Mana regenerated by a mage every round, up to the max mana.
+pub const MANA_REGEN: usize = 2;
+/// Max mana of a mage without a configured one.
+pub const DEFAULT_MAX_MANA: usize = 20;
+/// Mana consumed by a heal of a mage without a configured cost.
+pub const DEFAULT_HEAL_COST: usize = 4;
+/// Life points healed by a mage without a configured base heal, even without a staff.
+pub const DEFAULT_BASE_HEAL: usize = 5;
+/// Burn damage of the first round after a hit of a `Fire` enchantment, halved every round, see `Entity::burn`.
+pub const FIRE_BURN_DMG: usize = 6;
+/// Dexterity penalty of a target hit by a `Frost` enchantment.
+pub const FROST_DEXTERITY_PENALTY: usize = 5;
+/// Divisor of the dealt damage which a `Vampiric` enchantment heals.
+pub const VAMPIRIC_HEAL_DIVISOR: usize = 4;
+/// Damage of an off-hand weapon in percent of its full damage.
+pub const OFF_HAND_DAMAGE_PERCENT: usize = 50;
+/// Endurance a fighter needs for stunning hits, see `Combatant::can_stun`.
+pub const STUN_ENDURANCE: usize = 5;
+/// Endurance a fighter needs for a chance to break the guard of a defending enemy, see `Combatant::can_break_guard`.
+pub const GUARD_BREAK_ENDURANCE: usize = 8;
+/// Dexterity a combatant needs to exceed for a chance of extra attacks, see `Combatant::attack_turn`.
+pub const EXTRA_ATTACK_DEXTERITY: usize = 15;
+/// Maximum count of extra attacks in a single turn.
+pub const MAX_EXTRA_ATTACKS: usize = 2;
+/// Life points a fighter heals per endurance in percent of the applied damage of a hit.
+pub const LIFESTEAL_PERCENT: usize = 5;
+/// Maximum life points a fighter heals in percent of the applied damage of a hit, whatever its endurance.
+pub const MAX_LIFESTEAL_PERCENT: usize = 25;
+/// Life points in percent of the max life points below which a `Fleeing` monster tries to flee.
+pub const MONSTER_FLEE_THRESHOLD: usize = 25;
+/// Life points in percent of the max life points below which a monster on `Hard` may heal itself.
+pub const MONSTER_HEAL_THRESHOLD: usize = 50;
+/// Percent of the max life points a monster heals, see `Monster::heal`.
+pub const MONSTER_HEAL_PERCENT: usize = 20;
+/// Percent a monster without a configured scaling gains per level of the player above the first.
+pub const DEFAULT_LEVEL_SCALING: usize = 10;
+/// Rounds a taunt draws the attacks of the enemy in a party fight, counting the round of the taunt.
+pub const TAUNT_ROUNDS: usize = 3;
+/// Divisor a shield decays by at the start of every round, see `Entity::shield`.
+pub const SHIELD_DECAY_DIVISOR: usize = 2;
+/// Divisor of the rolled damage a fumbling attacker takes itself on `Hard`, see `AttackRoll::Fumble`.
+pub const FUMBLE_SELF_DMG_DIVISOR: usize = 2;
+/// Bonus damage of an attack for every attack in a row before it, see `Entity::combo`.
+pub const COMBO_DAMAGE_BONUS: usize = 2;
+/// Times the initiative is rolled again on a tie with `TieBreak::Reroll`, before the defender gets the first turn.
+pub const MAX_INITIATIVE_REROLLS: usize = 10;
+/// Percent of the life points left after a win, from which a `Dynamic` difficulty gets harder.
+pub const DYNAMIC_DOMINANT_PERCENT: usize = 75;
+/// Percent of the life points left after a win, up to which a `Dynamic` difficulty gets easier.
+pub const DYNAMIC_BARELY_PERCENT: usize = 25;
+/// Bounds of the dice sides of a `Dynamic` difficulty, from `Easy` to `Hard`.
+pub const DYNAMIC_DICE_SIDES: RangeInclusive<usize> = 3..=9;
 
 /// The general Entity type.
 ///
 /// Every in game living thing is an entity: The Player and the Enemies.
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Entity {
     name: String,
     life_points: usize,
+    /// Upper bound for healing. Defaults to the starting `life_points` if not set.
+    #[serde(default)]
+    max_life_points: usize,
     dexterity: usize,
     strength: usize,
     weapon: Option<Weapon>,
+    /// Second weapon, which adds `OFF_HAND_DAMAGE_PERCENT` of its damage.
+    #[serde(default)]
+    off_hand: Option<Weapon>,
+    #[serde(default)]
+    armor: usize,
+    /// Halves the next incoming damage, reset after being hit.
+    #[serde(skip)]
+    defending: bool,
+    #[serde(default)]
+    experience: usize,
+    #[serde(default = "default_level")]
+    level: usize,
+    /// Remaining rounds the entity is poisoned.
+    #[serde(skip)]
+    poison_turns: usize,
+    /// Damage dealt by the poison each round.
+    #[serde(skip)]
+    poison_dmg: usize,
+    /// Damage dealt by the burn in the next round, halved every round.
+    #[serde(skip)]
+    burn_stack: usize,
+    /// Lowers the dexterity for the next dodge, set by a `Frost` enchantment.
+    #[serde(skip)]
+    frozen: bool,
+    /// Remaining rounds until fleeing is possible again.
+    #[serde(skip)]
+    flee_cooldown: usize,
+    /// Skips the next turn, set by a stunning hit.
+    #[serde(skip)]
+    stunned: bool,
+    /// Remaining rounds the entity draws the attacks of the enemy in a party fight, see `Combatant::taunt`.
+    #[serde(skip)]
+    taunt_turns: usize,
+    /// Absorbs incoming damage before the life points, gained by overhealing (see `Mage::heal`).
+    /// Divided by `SHIELD_DECAY_DIVISOR` at the start of every round.
+    #[serde(skip)]
+    shield: usize,
+    /// Count of attacks in a row, reset by any other action or by getting hit.
+    #[serde(skip)]
+    combo: usize,
+    /// Damage dealt by attacks in the current fight.
+    #[serde(skip)]
+    damage_dealt: usize,
+    /// Damage taken by attacks in the current fight.
+    #[serde(skip)]
+    damage_taken: usize,
+    /// Weapon materials of which the entity takes only half the weapon damage.
+    #[serde(default)]
+    resistances: Vec<Material>,
+    /// Count of healing potions.
+    #[serde(default)]
+    potions: usize,
+    /// Count of antidotes, each curing the poison, burn and stun (see `Entity::cleanse`).
+    #[serde(default)]
+    antidotes: usize,
+    /// Life points recovered at the start of every round.
+    #[serde(default)]
+    regen: usize,
+    /// Percent of the physical damage taken which is reflected to the attacker, see `Combatant::attack`.
+    #[serde(default)]
+    thorns: usize,
+    /// Spare weapons, which can be swapped with the `weapon` in a fight.
+    #[serde(default)]
+    inventory: Vec<Weapon>,
+    /// Damage taken from physical attacks in percent, see `DamageType`.
+    #[serde(default = "default_weakness")]
+    physical_weakness: usize,
+    /// Damage taken from magic attacks in percent, see `DamageType`.
+    #[serde(default = "default_weakness")]
+    magic_weakness: usize,
+}
+
+fn default_level() -> usize {
+    1
+}
+
+fn default_weakness() -> usize {
+    100
+}
+
+impl Default for Entity {
+    fn default() -> Self {
+        Self::new(String::new(), 0, 0, 0, None, 0)
+    }
 }
 
 impl Entity {
@@ -25,425 +225,5153 @@ impl Entity {
         dexterity: usize,
         strength: usize,
         weapon: Option<Weapon>,
+        armor: usize,
     ) -> Self {
         Self {
             name,
             life_points,
+            max_life_points: life_points,
             dexterity,
             strength,
             weapon,
+            off_hand: None,
+            armor,
+            defending: false,
+            experience: 0,
+            level: default_level(),
+            poison_turns: 0,
+            poison_dmg: 0,
+            burn_stack: 0,
+            frozen: false,
+            flee_cooldown: 0,
+            stunned: false,
+            taunt_turns: 0,
+            shield: 0,
+            combo: 0,
+            damage_dealt: 0,
+            damage_taken: 0,
+            resistances: vec![],
+            potions: 0,
+            antidotes: 0,
+            regen: 0,
+            thorns: 0,
+            inventory: vec![],
+            physical_weakness: default_weakness(),
+            magic_weakness: default_weakness(),
         }
     }
 
-    pub fn apply_dmg(&mut self, dmg: usize) -> bool {
-        self.life_points = self.life_points.saturating_sub(dmg);
-        self.life_points == 0
+    /// The experience gained by defeating this entity.
+    pub fn xp_reward(&self) -> usize {
+        self.max_life_points
+            .saturating_add(self.strength)
+            .saturating_add(self.dexterity)
     }
-}
 
-/// Everything which should be able to fight, needs to implement this trait.
-///
-/// The trait name `Combatant` is from ChatGPT.
-pub trait Combatant {
-    /// Gets a reference of the entity.
-    fn entity(&self) -> &Entity;
+    /// Adds `amount` to the experience and levels up for every crossed `level * XP_PER_LEVEL` threshold.
+    pub fn award_xp(&mut self, amount: usize) {
+        self.experience = self.experience.saturating_add(amount);
+        reveal(&msg(Msg::XpGained, &[&self.name, &amount]), delay());
+        while self
+            .level
+            .checked_mul(XP_PER_LEVEL)
+            .is_some_and(|threshold| self.experience >= threshold)
+        {
+            self.level_up();
+        }
+    }
 
-    /// Gets a mutable reference of the entity.
-    fn entity_mut(&mut self) -> &mut Entity;
+    /// Increases the level and strengthens the entity.
+    pub fn level_up(&mut self) {
+        self.level = self.level.saturating_add(1);
+        self.strength = self.strength.saturating_add(LEVEL_UP_STRENGTH);
+        self.life_points = self.life_points.saturating_add(LEVEL_UP_LIFE_POINTS);
+        self.max_life_points = self.max_life_points.saturating_add(LEVEL_UP_LIFE_POINTS);
+        reveal(&msg(Msg::LevelUp, &[&self.name, &self.level]), delay());
+    }
 
-    /// Determine Attack Damage. This function has a default implementation
-    /// which can be overwritten (Polymorphism).
-    fn attack_damage(&self) -> usize {
-        let entity = self.entity();
-        if let Some(weapon) = &entity.weapon {
-            weapon.calc_damage() + entity.strength
+    /// Gets the name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the dexterity reduced by `TWO_HANDED_DEXTERITY_PENALTY` if wielding a two-handed weapon.
+    pub fn effective_dexterity(&self) -> usize {
+        let dexterity = match &self.weapon {
+            Some(weapon) if weapon.two_handed => {
+                self.dexterity.saturating_sub(TWO_HANDED_DEXTERITY_PENALTY)
+            }
+            _ => self.dexterity,
+        };
+        if self.frozen {
+            dexterity.saturating_sub(FROST_DEXTERITY_PENALTY)
         } else {
-            entity.strength
+            dexterity
         }
     }
 
-    /// Attacks the `enemy` and subtracts the applied damage to it.
-    /// Returns true if enemy is defeated!
-    fn attack<E: Combatant>(&mut self, enemy: &mut E) -> bool {
-        let self_dmg = self.attack_damage();
-        let self_entity = self.entity();
-        let enemy_entity = enemy.entity_mut();
-        if enemy_entity.apply_dmg(self_dmg) {
-            reveal(
-                &format!(
-                    "Attacke von `{}` hat `{}` besiegt!\n",
-                    &self_entity.name, &enemy_entity.name
-                ),
-                TIME_BETWEEN,
-            );
-            true
-        } else {
-            reveal(
-                &format!(
-                    "Attacke von `{}` hat mit einem Schaden von {} getroffen!\n",
-                    &self_entity.name, self_dmg
-                ),
-                TIME_BETWEEN,
-            );
-            false
+    /// Resets the fight statistics, the flee cooldown and the stun at the start of a fight.
+    fn begin_fight(&mut self) {
+        self.damage_dealt = 0;
+        self.damage_taken = 0;
+        self.flee_cooldown = 0;
+        self.stunned = false;
+        self.combo = 0;
+        self.taunt_turns = 0;
+        self.shield = 0;
+    }
+
+    /// Counts down the flee cooldown and regenerates `regen` life points (if alive) at the start of a round.
+    fn begin_round(&mut self, game_rules: &mut GameRules) {
+        self.flee_cooldown = self.flee_cooldown.saturating_sub(1);
+        self.taunt_turns = self.taunt_turns.saturating_sub(1);
+        self.shield /= SHIELD_DECAY_DIVISOR;
+        if self.is_alive() {
+            let heal_lp = self.heal(self.regen);
+            if heal_lp > 0 {
+                reveal(&msg(Msg::Regenerated, &[&self.name, &heal_lp]), delay());
+                game_rules.log(&self.name, LogAction::Heal, heal_lp);
+            }
         }
     }
 
-    /// Selector for what the combatant want to do next.
-    /// Default is that the `Combatant` can either attack of flee!
-    ///
-    /// Returns `true` if the enemy is dead or fleeing was successful!
-    fn select_action<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool {
-        let attack_dmg = self.attack_damage();
-        let n = game_rules.dice.n;
-        let options: [&str; 2] = [
-            &format!("Angreifen ({attack_dmg} Lebenspunkte Schaden)"),
-            &format!("Fliehen (1/{n} Chance)"),
-        ];
-        let i = select("Aktion auswählen (Pfeiltasten, Enter)", &options);
-
-        match options[i] {
-            option if option.starts_with("Angreifen") => self.attack(enemy),
-            option if option.starts_with("Fliehen") => {
-                let success = game_rules.dice.throw_dice();
-                if success {
-                    reveal("Fliehen war erfolgreich!\n", TIME_BETWEEN);
+    /// Takes the `loot` of a defeated monster: Equips a weapon if it deals more damage with the `proficiency`
+    /// of the combatant (see `Combatant::proficiency`) or boosts the stats.
+    pub fn take_loot(&mut self, loot: Loot, proficiency: Proficiency) {
+        match loot {
+            Loot::Nothing => reveal(&msg(Msg::LootNothing, &[&self.name]), delay()),
+            Loot::Weapon(weapon) => {
+                let damage = weapon.proficient_damage(proficiency);
+                let better = self
+                    .weapon
+                    .as_ref()
+                    .is_none_or(|current| damage > current.proficient_damage(proficiency));
+                if better && !self.can_wield(&weapon) {
+                    // Kept for later, see `unequip_locked_weapons`
+                    reveal(
+                        &msg(
+                            Msg::WeaponLocked,
+                            &[&self.name, &weapon.material, &weapon.min_level()],
+                        ),
+                        delay(),
+                    );
+                    self.inventory.push(weapon);
+                } else if better {
+                    reveal(&msg(Msg::LootWeapon, &[&self.name, &damage]), delay());
+                    self.weapon = Some(weapon);
                 } else {
-                    reveal("Fliehen war nicht erfolgreich!\n", TIME_BETWEEN);
+                    reveal(&msg(Msg::LootWeaponDiscarded, &[&self.name]), delay());
                 }
-                success
             }
-            _ => unimplemented!(),
+            Loot::Strength(boost) => {
+                self.strength = self.strength.saturating_add(boost);
+                reveal(&msg(Msg::LootStrength, &[&self.name, &boost]), delay());
+            }
+            Loot::Dexterity(boost) => {
+                self.dexterity = self.dexterity.saturating_add(boost);
+                reveal(&msg(Msg::LootDexterity, &[&self.name, &boost]), delay());
+            }
         }
     }
 
-    /// Simulates a fight against an `enemy` with a set of `game_rules`.
-    /// Runs until `self` or `enemy` is dead (has 0 `life_points`).
-    fn fight<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules)
-    where
-        Self: Sized,
-    {
-        // Determine fight order; Enemy has constant dexterity; the initiator of the fight, `self`, has to roll
-        let ordering = if game_rules.dice.apply_dice_roll(self.entity().dexterity)
-            > enemy.entity().dexterity
-        {
-            Ordering::Player
-        } else {
-            Ordering::Enemy
-        };
+    /// Removes surrounding whitespace from the name.
+    pub fn trim_name(&mut self) {
+        let trimmed = self.name.trim();
+        if trimmed.len() != self.name.len() {
+            self.name = trimmed.into();
+        }
+    }
 
-        reveal(
-            &format!("{ordering:?} wird zuerst angreifen!\n"),
-            TIME_BETWEEN,
-        );
+    /// Gets the current life points.
+    pub fn life_points(&self) -> usize {
+        self.life_points
+    }
 
-        // Fight until one is dead
-        let mut i = 0;
-        loop {
-            reveal(&format!("Runde {} hat begonnen!\n", i + 1,), TIME_BETWEEN);
-            i += 1;
+    /// Returns true if the entity has any life points left.
+    pub fn is_alive(&self) -> bool {
+        self.life_points > 0
+    }
 
-            reveal(
-                &format!(
-                    "`{}` hat {} Lebenspunkte und `{}` hat {} Lebenspunkte!\n",
-                    self.entity().name,
-                    self.entity().life_points,
-                    enemy.entity().name,
-                    enemy.entity().life_points
-                ),
-                TIME_BETWEEN,
-            );
+    pub fn level(&self) -> usize {
+        self.level
+    }
 
-            match ordering {
-                Ordering::Player => {
-                    if self.select_action(enemy, game_rules) {
-                        break;
-                    }
-                    if enemy.select_action(self, game_rules) {
-                        break;
-                    }
-                }
-                Ordering::Enemy => {
-                    if enemy.select_action(self, game_rules) {
-                        break;
-                    }
-                    if self.select_action(enemy, game_rules) {
-                        break;
-                    }
-                }
-            }
-        }
+    /// Scales the life points (current and max) and the strength by `percent`:
+    /// `stat * percent / 100`, rounded down but at least 1 life point. Huge stats saturate instead of overflowing.
+    pub fn scale_stats(&mut self, percent: usize) {
+        let scale = |stat: usize| stat.saturating_mul(percent) / 100;
+        self.life_points = scale(self.life_points).max(1);
+        self.max_life_points = scale(self.max_life_points).max(1);
+        self.strength = scale(self.strength);
     }
-}
 
-/// General Game Rules.
-pub struct GameRules {
-    dice: Dice,
-}
+    /// Gets the damage dealt by attacks in the current (or last) fight.
+    pub fn total_damage_dealt(&self) -> usize {
+        self.damage_dealt
+    }
 
-impl GameRules {
-    pub fn new(difficulty: Difficulty) -> Self {
-        Self {
-            dice: Dice::new(difficulty.to_dice_n()),
-        }
+    /// Gets the damage taken by attacks in the current (or last) fight.
+    /// Heals and poison don't count.
+    pub fn total_damage_taken(&self) -> usize {
+        self.damage_taken
     }
-}
 
-/// Dice with `n` sides.
-///
-/// In rust, there are no random functions in it's `std`-library.
-/// Therefore using the `rngs`-crate for that!
-struct Dice {
-    n: usize,
-    rng: SmallRng,
-}
+    /// Gets the max life points of the entity.
+    pub fn max_life_points(&self) -> usize {
+        self.max_life_points
+    }
 
-impl Dice {
-    pub fn new(n: usize) -> Self {
-        Self {
-            n,
-            rng: SmallRng::from_os_rng(),
-        }
+    /// Gets the dexterity, without the penalties of `effective_dexterity`.
+    pub fn dexterity(&self) -> usize {
+        self.dexterity
     }
 
-    /// Applys a dice roll to a number by calculating
-    /// `(random_range(0..=n) / n) * n` and returning the result.
-    pub fn apply_dice_roll(&mut self, num: usize) -> usize {
-        let n = self.n;
-        ((self.rng.random_range(1..=n) as f64 / n as f64) * num as f64).floor() as usize
+    /// Gets the strength.
+    pub fn strength(&self) -> usize {
+        self.strength
     }
 
-    /// Returns true if dice rolled `n`
-    pub fn throw_dice(&mut self) -> bool {
-        let n = self.n;
-        self.rng.random_range(1..=n) == n
+    /// Gets the weapon, `None` if the entity has none (or it broke).
+    pub fn weapon(&self) -> Option<&Weapon> {
+        self.weapon.as_ref()
     }
-}
 
-/// Difficulty used for setting up Game Rules and Dice sides.
-#[derive(Debug, Clone, Copy)]
-pub enum Difficulty {
-    /// Dice changes to 1/3
-    Easy,
-    /// Dice changes to 1/6
-    Normal,
-    /// Dice changes to 1/9
-    Hard,
-}
+    /// Gets the second weapon, `None` if the entity doesn't dual-wield.
+    pub fn off_hand(&self) -> Option<&Weapon> {
+        self.off_hand.as_ref()
+    }
 
-impl Difficulty {
-    /// Returns `Difficulty` from `i`. i has to be 0 <= i <= 2 otherwise this function panics!
-    pub fn from_i(i: usize) -> Self {
-        match i {
-            0 => Self::Easy,
-            1 => Self::Normal,
-            2 => Self::Hard,
-            _ => unreachable!(),
-        }
+    /// Gets the spare weapons.
+    pub fn inventory(&self) -> &[Weapon] {
+        &self.inventory
     }
-    /// Converts the current difficulty to the count of dice sides.
-    pub fn to_dice_n(&self) -> usize {
-        match self {
-            Self::Easy => 3,
-            Self::Normal => 6,
-            Self::Hard => 9,
-        }
+
+    pub fn potions(&self) -> usize {
+        self.potions
     }
-}
 
-/// A mage (player) with the option to heal themselves.
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct Mage {
-    pub entity: Entity,
-    magic_power: usize,
-}
+    pub fn antidotes(&self) -> usize {
+        self.antidotes
+    }
 
-impl Combatant for Mage {
-    fn entity(&self) -> &Entity {
-        &self.entity
+    /// Gets every weapon: The `weapon`, the `off_hand` and the `inventory`.
+    pub fn weapons(&self) -> impl Iterator<Item = &Weapon> {
+        self.weapon
+            .iter()
+            .chain(self.off_hand.iter())
+            .chain(self.inventory.iter())
     }
 
-    fn entity_mut(&mut self) -> &mut Entity {
-        &mut self.entity
+    /// Gets the percent a flee is held back by the weight of every carried weapon (see `Weapon::weight`):
+    /// `FLEE_WEIGHT_PENALTY_PERCENT` per unit of weight, at most `MAX_FLEE_WEIGHT_PENALTY`.
+    /// So the flee chance is `1/n * (100 - penalty) / 100` for a single die.
+    pub fn flee_penalty(&self) -> usize {
+        let weight: usize = self.weapons().map(Weapon::weight).sum();
+        weight
+            .saturating_mul(FLEE_WEIGHT_PENALTY_PERCENT)
+            .min(MAX_FLEE_WEIGHT_PENALTY)
     }
 
-    /// Overwriting the default implementation for `select_action` by adding an heal option.
-    fn select_action<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool {
-        let attack_dmg = self.attack_damage();
-        let heal_lp = self.get_heal_lp();
-        let n = game_rules.dice.n;
-        let options: [&str; 3] = [
-            &format!("Angreifen ({attack_dmg} Lebenspunkte Schaden)"),
-            &format!("Selber heilen ({heal_lp} Lebenspunkte)"),
-            &format!("Fliehen (1/{n} Chance)"),
-        ];
-        let i = select("Aktion auswählen (Pfeiltasten, Enter)", &options);
+    /// Gets the stats affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 13] {
+        [
+            ("level", self.level),
+            ("experience", self.experience),
+            ("life_points", self.life_points),
+            ("max_life_points", self.max_life_points),
+            ("dexterity", self.dexterity),
+            ("strength", self.strength),
+            ("armor", self.armor),
+            ("potions", self.potions),
+            ("antidotes", self.antidotes),
+            ("regen", self.regen),
+            ("thorns", self.thorns),
+            ("physical_weakness", self.physical_weakness),
+            ("magic_weakness", self.magic_weakness),
+        ]
+    }
 
-        match options[i] {
-            option if option.starts_with("Angreifen") => self.attack(enemy),
-            option if option.starts_with("Selber heilen") => {
-                self.heal();
-                false
-            }
-            option if option.starts_with("Fliehen") => {
-                let success = game_rules.dice.throw_dice();
-                if success {
-                    reveal("Fliehen war erfolgreich!\n", TIME_BETWEEN);
-                } else {
-                    reveal("Fliehen war nicht erfolgreich!\n", TIME_BETWEEN);
-                }
-                success
-            }
-            _ => unimplemented!(),
+    /// Equips the weapon `i` of the inventory, moving the current weapon (if any) into the inventory.
+    /// Panics if `i` is out of bounds!
+    pub fn swap_weapon(&mut self, i: usize) {
+        let weapon = self.inventory.remove(i);
+        if let Some(current) = self.weapon.replace(weapon) {
+            self.inventory.push(current);
         }
     }
-}
 
-impl Mage {
-    pub fn new(entity: Entity, magic_power: usize) -> Self {
-        Self {
-            entity,
-            magic_power,
+    /// Returns true if the level is high enough for the material of the `weapon` (see `Material::min_level`).
+    pub fn can_wield(&self, weapon: &Weapon) -> bool {
+        self.level >= weapon.min_level()
+    }
+
+    /// Moves every equipped weapon the entity can't wield yet (see `can_wield`) into the inventory with a warning.
+    /// If the main weapon was unequipped, the strongest wieldable one of the inventory is equipped instead, if any.
+    pub fn unequip_locked_weapons(&mut self) {
+        let level = self.level;
+        let armed = self.weapon.is_some();
+        for hand in [&mut self.weapon, &mut self.off_hand] {
+            if let Some(weapon) = hand.take_if(|weapon| weapon.min_level() > level) {
+                reveal(
+                    &msg(
+                        Msg::WeaponLocked,
+                        &[&self.name, &weapon.material, &weapon.min_level()],
+                    ),
+                    delay(),
+                );
+                self.inventory.push(weapon);
+            }
+        }
+        if armed && self.weapon.is_none() {
+            let strongest = (0..self.inventory.len())
+                .filter(|&i| self.can_wield(&self.inventory[i]))
+                .max_by_key(|&i| self.inventory[i].calc_damage());
+            if let Some(i) = strongest {
+                self.swap_weapon(i);
+            }
         }
     }
 
-    /// Calculates the heal lp and returns it.
-    pub fn get_heal_lp(&self) -> usize {
-        let weapon_power = if let Some(weapon) = &self.entity.weapon {
-            weapon.spell_power
-        } else {
-            0
-        };
-        self.magic_power * weapon_power
+    /// Gets the damage of the weapons: The full damage of the `weapon`
+    /// plus `OFF_HAND_DAMAGE_PERCENT` of the `off_hand`, `0` without any.
+    pub fn weapon_damage(&self) -> usize {
+        self.proficient_weapon_damage(Proficiency::FULL)
     }
 
-    /// Applys the heal of the mage to it's own health.
-    pub fn heal(&mut self) {
-        let heal_lp = self.get_heal_lp();
-        self.entity.life_points += heal_lp;
-        reveal(
-            &format!(
-                "`{}` hat sich mit {} Lebenspunkten geheilt!\n",
-                self.entity.name, heal_lp
-            ),
-            TIME_BETWEEN,
-        )
+    /// Same as `weapon_damage` but only with the parts of the weapons usable with the `proficiency`.
+    pub fn proficient_weapon_damage(&self, proficiency: Proficiency) -> usize {
+        let damage = |weapon: &Weapon| weapon.proficient_damage(proficiency);
+        let main = self.weapon.as_ref().map_or(0, damage);
+        let off_hand = self.off_hand.as_ref().map_or(0, |weapon| {
+            damage(weapon).saturating_mul(OFF_HAND_DAMAGE_PERCENT) / 100
+        });
+        main.saturating_add(off_hand)
     }
-}
 
-/// A fighter (player) with extra endurance which strengthens their attack damage.
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct Fighter {
-    pub entity: Entity,
-    endurance: usize,
-}
+    /// Gets the damage taken from attacks of the `damage_type` in percent, `100` by default.
+    pub fn weakness(&self, damage_type: DamageType) -> usize {
+        match damage_type {
+            DamageType::Physical => self.physical_weakness,
+            DamageType::Magic => self.magic_weakness,
+        }
+    }
 
-impl Combatant for Fighter {
-    fn entity(&self) -> &Entity {
-        &self.entity
+    /// Poisons the entity for `POISON_TURNS` rounds with `dmg` damage each round.
+    pub fn poison(&mut self, dmg: usize) {
+        self.poison_turns = POISON_TURNS;
+        self.poison_dmg = dmg;
+        reveal(&msg(Msg::Poisoned, &[&self.name]), delay());
     }
 
-    fn entity_mut(&mut self) -> &mut Entity {
-        &mut self.entity
+    /// Sets the entity on fire, adding `dmg` to the burn stack. Unlike a poison the burn
+    /// deals less damage every round, as the stack halves after every tick (see `tick_burn`).
+    pub fn burn(&mut self, dmg: usize) {
+        self.burn_stack = self.burn_stack.saturating_add(dmg);
+        reveal(&msg(Msg::Burning, &[&self.name]), delay());
     }
 
-    /// Overwriting the default implementation for `attack_damage` by adding an endurance multiplier.
-    fn attack_damage(&self) -> usize {
-        let entity = self.entity();
-        let norm_attack = if let Some(weapon) = &entity.weapon {
-            weapon.calc_damage() + entity.strength
-        } else {
-            entity.strength
-        };
-        norm_attack * self.endurance
+    /// Returns true if the entity is poisoned, burning or stunned, which an antidote cures.
+    pub fn has_status(&self) -> bool {
+        self.poison_turns > 0 || self.burn_stack > 0 || self.stunned
     }
-}
 
-impl Fighter {
-    pub fn new(entity: Entity, endurance: usize) -> Self {
-        Self { entity, endurance }
+    /// Cures the poison, burn and stun.
+    pub fn cleanse(&mut self) {
+        self.poison_turns = 0;
+        self.poison_dmg = 0;
+        self.burn_stack = 0;
+        self.stunned = false;
     }
-}
 
-/// A monster struct which the player fights against.
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct Monster {
-    pub entity: Entity,
-}
+    /// Ticks the poison and the burn at the start of a round, see `tick_poison` and `tick_burn`.
+    /// Returns true if the entity is defeated by them!
+    pub fn tick_statuses(&mut self, game_rules: &mut GameRules) -> bool {
+        self.tick_poison(game_rules) || self.tick_burn(game_rules)
+    }
 
-impl Combatant for Monster {
-    fn entity(&self) -> &Entity {
-        &self.entity
+    /// Applies the burn damage (ignoring armor) if the entity is burning and halves the burn stack.
+    /// Returns true if the entity is defeated by the burn!
+    pub fn tick_burn(&mut self, game_rules: &mut GameRules) -> bool {
+        if self.burn_stack == 0 {
+            return false;
+        }
+        let dmg = self.burn_stack;
+        self.burn_stack /= 2;
+        self.life_points = self.life_points.saturating_sub(dmg);
+        game_rules.log(&self.name, LogAction::Burn, dmg);
+        if self.life_points == 0 {
+            reveal(&msg(Msg::BurnDefeated, &[&self.name]), delay());
+            true
+        } else {
+            reveal(&msg(Msg::BurnDamage, &[&self.name, &dmg]), delay());
+            false
+        }
     }
 
-    fn entity_mut(&mut self) -> &mut Entity {
-        &mut self.entity
+    /// Applies the poison damage (ignoring armor) if the entity is poisoned.
+    /// Returns true if the entity is defeated by the poison!
+    pub fn tick_poison(&mut self, game_rules: &mut GameRules) -> bool {
+        if self.poison_turns == 0 {
+            return false;
+        }
+        self.poison_turns -= 1;
+        self.life_points = self.life_points.saturating_sub(self.poison_dmg);
+        game_rules.log(&self.name, LogAction::Poison, self.poison_dmg);
+        if self.life_points == 0 {
+            reveal(&msg(Msg::PoisonDefeated, &[&self.name]), delay());
+            true
+        } else {
+            reveal(
+                &msg(Msg::PoisonDamage, &[&self.name, &self.poison_dmg]),
+                delay(),
+            );
+            false
+        }
     }
 
-    /// Overwriting the default implementation for `select_action` by removing all options.
-    /// A monster will always attack.
-    fn select_action<E: Combatant>(&mut self, enemy: &mut E, _game_rules: &mut GameRules) -> bool {
-        self.attack(enemy)
+    /// Wears the weapon down by one use. Once its durability reaches 0 the weapon breaks.
+    pub fn wear_weapon(&mut self) {
+        if let Some(weapon) = &mut self.weapon {
+            if let Some(durability) = &mut weapon.durability {
+                *durability = durability.saturating_sub(1);
+                if *durability == 0 {
+                    self.weapon = None;
+                    reveal(&msg(Msg::WeaponBroken, &[&self.name]), delay());
+                }
+            }
+        }
     }
-}
 
-impl Monster {
-    pub fn new(entity: Entity) -> Self {
-        Self { entity }
+    /// Sets `max_life_points` to the starting `life_points` if it wasn't set (e.g. in the config).
+    pub fn init_max_life_points(&mut self) {
+        if self.max_life_points == 0 {
+            self.max_life_points = self.life_points;
+        }
     }
-}
 
-/// Weapon can have different material and a spell power (if seen as a staff).
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Weapon {
-    material: Material,
-    pub spell_power: usize,
-}
+    /// Heals the entity by `lp` without exceeding `max_life_points`.
+    /// Returns the actually healed life points.
+    pub fn heal(&mut self, lp: usize) -> usize {
+        let before = self.life_points;
+        self.life_points = self
+            .life_points
+            .saturating_add(lp)
+            .min(self.max_life_points.max(before));
+        self.life_points - before
+    }
 
-impl Weapon {
-    pub fn new(material: Material, spell_power: usize) -> Self {
-        Self {
-            material,
-            spell_power,
-        }
+    /// Reduces `dmg` by halving it if the entity is defending and then by the `armor` of the entity.
+    /// At least 1 damage is always dealt, so a fight can't stall.
+    pub fn reduce_dmg(&self, dmg: usize) -> usize {
+        let dmg = if self.defending { dmg / 2 } else { dmg };
+        dmg.saturating_sub(self.armor).max(1)
     }
 
-    /// Calculate damage modifier of the weapon.
-    pub fn calc_damage(&self) -> usize {
-        self.material.calc_modifier() + self.spell_power
+    /// Gets the damage the shield absorbs before the life points.
+    pub fn shield(&self) -> usize {
+        self.shield
     }
-}
 
-// Material of the weapon. `Wood` is the weakest and `Diamond` the strongest material.
-#[repr(usize)]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum Material {
-    Wood = 1,
-    Stone,
-    Iron,
-    Gold,
-    MagicOre,
-    Diamond,
+    /// Applies the reduced `dmg` and ends defending. The shield absorbs the damage first.
+    /// Only the life points actually lost count as taken, without the overkill.
+    /// Returns true if the entity is defeated!
+    pub fn apply_dmg(&mut self, dmg: usize) -> bool {
+        let dmg = self.reduce_dmg(dmg);
+        self.defending = false;
+        let absorbed = dmg.min(self.shield);
+        self.shield -= absorbed;
+        let dmg = dmg - absorbed;
+        self.damage_taken += dmg.min(self.life_points);
+        self.life_points = self.life_points.saturating_sub(dmg);
+        self.life_points == 0
+    }
 }
 
-impl Material {
-    // Calculating the material modifier. Used for damage calculation.
-    pub fn calc_modifier(&self) -> usize {
-        *self as usize
+/// A one-line summary for a character sheet, e.g. "`Hero`: 40/50 Lebenspunkte, Stärke 4, ...".
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weapon = match &self.weapon {
+            Some(weapon) => msg(Msg::SheetWeapon, &[&weapon.material, &weapon.calc_damage()]),
+            None => text(Msg::SheetNoWeapon).into(),
+        };
+        let sheet = msg(
+            Msg::SheetEntity,
+            &[
+                &self.name,
+                &self.life_points,
+                &self.max_life_points,
+                &self.strength,
+                &self.dexterity,
+                &weapon,
+            ],
+        );
+        write!(f, "{sheet}")
     }
 }
 
-/// Fight order.
-enum Ordering {
-    Player,
-    Enemy,
-}
+/// Everything which should be able to fight, needs to implement this trait.
+///
+/// The trait name `Combatant` is from ChatGPT.
+pub trait Combatant {
+    /// Gets a reference of the entity.
+    fn entity(&self) -> &Entity;
 
-impl Debug for Ordering {
+    /// Gets a mutable reference of the entity.
+    fn entity_mut(&mut self) -> &mut Entity;
+
+    /// Gets the name shown in the messages, which defaults to the name of the entity.
+    /// Can be overridden for a display name per class, e.g. "Magier Gandalf".
+    fn name(&self) -> &str {
+        &self.entity().name
+    }
+
+    /// Returns true if the combatant has any life points left, see `Entity::is_alive`.
+    fn is_alive(&self) -> bool {
+        self.entity().is_alive()
+    }
+
+    /// Determine Attack Damage. This function has a default implementation
+    /// which can be overwritten (Polymorphism).
+    /// The damage saturates at `usize::MAX` instead of overflowing.
+    fn attack_damage(&self) -> usize {
+        let entity = self.entity();
+        entity
+            .proficient_weapon_damage(self.proficiency())
+            .saturating_add(entity.strength)
+    }
+
+    /// Gets the part of `attack_damage` dealt by the weapons alone (see `Entity::proficient_weapon_damage`),
+    /// scaled like `attack_damage` of the class. A resisting enemy takes only half of it, see `attack`.
+    fn weapon_attack_damage(&self) -> usize {
+        self.entity().proficient_weapon_damage(self.proficiency())
+    }
+
+    /// Gets how well the combatant can use the parts of its weapons, see `Proficiency`.
+    /// Defaults to `Proficiency::FULL`.
+    fn proficiency(&self) -> Proficiency {
+        Proficiency::FULL
+    }
+
+    /// Reveals a warning if the combatant can't use its weapons fully, see `proficiency`.
+    fn warn_proficiency(&self) {
+        let entity = self.entity();
+        let proficient = entity.proficient_weapon_damage(self.proficiency());
+        let full = entity.weapon_damage();
+        if proficient < full {
+            reveal(
+                &msg(Msg::NotProficient, &[&self.name(), &proficient, &full]),
+                delay(),
+            );
+        }
+    }
+
+    /// Gets the type of the damage dealt by attacks, which the enemy may be weak to (see `Entity::weakness`).
+    /// Defaults to `DamageType::Physical`.
+    fn damage_type(&self) -> DamageType {
+        DamageType::Physical
+    }
+
+    /// Attacks the `enemy` and subtracts the applied damage to it.
+    /// The `enemy` may dodge the attack, see `Dice::roll_dodge`.
+    /// If the `enemy` resists the material of the weapon, the weapon damage is halved, see `weapon_attack_damage`.
+    /// The damage is then scaled by the weakness of the `enemy` to the `damage_type`.
+    /// Every attack in a row before this one adds `COMBO_DAMAGE_BONUS`, a counterattack doesn't count.
+    /// A critical hit multiplies the damage by `CRIT_MULTIPLIER`.
+    ///
+    /// A hit of a combatant which `can_stun` or with a `Frost` weapon may stun the `enemy`, see `Dice::roll_stun`.
+    ///
+    /// If the `enemy` survives (and isn't stunned), it may counterattack once with damage divided by
+    /// `COUNTER_DAMAGE_DIVISOR`, see `Dice::roll_counter`. Counters can't be countered.
+    ///
+    /// Returns true if enemy is defeated or `self` was defeated by the counter!
+    fn attack<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool
+    where
+        Self: Sized,
+    {
+        if !game_rules.countering {
+            self.entity_mut().combo += 1;
+        }
+        // A frost only lasts for the next dodge
+        let dodged = game_rules
+            .dice
+            .roll_dodge(enemy.entity().effective_dexterity());
+        enemy.entity_mut().frozen = false;
+        if dodged {
+            reveal(&msg(Msg::Dodged, &[&enemy.name(), &self.name()]), delay());
+            game_rules.log(&enemy.entity().name, LogAction::Dodge, 0);
+            self.entity_mut().wear_weapon();
+            return false;
+        }
+
+        let mut self_dmg = game_rules.dice.roll_damage(self.attack_damage());
+        let attack_roll = game_rules.dice.roll_attack();
+        if attack_roll == AttackRoll::Fumble {
+            return self.fumble(self_dmg, game_rules);
+        }
+        let combo = self.entity().combo;
+        if combo > 1 && !game_rules.countering {
+            self_dmg = self_dmg.saturating_add((combo - 1).saturating_mul(COMBO_DAMAGE_BONUS));
+            reveal(&msg(Msg::Combo, &[&combo]), delay());
+        }
+        if let Some(weapon) = &self.entity().weapon {
+            if enemy.entity().resistances.contains(&weapon.material) {
+                self_dmg = self_dmg.saturating_sub(self.weapon_attack_damage() / 2);
+                reveal(&msg(Msg::Resisted, &[&enemy.name()]), delay());
+            }
+        }
+        let weakness = enemy.entity().weakness(self.damage_type());
+        self_dmg = self_dmg.saturating_mul(weakness) / 100;
+        if weakness > 100 {
+            reveal(&msg(Msg::Weakness, &[&enemy.name()]), delay());
+        }
+        if game_rules.countering {
+            self_dmg /= COUNTER_DAMAGE_DIVISOR;
+        }
+        if attack_roll == AttackRoll::Crit {
+            self_dmg = self_dmg.saturating_mul(CRIT_MULTIPLIER);
+            reveal(text(Msg::CriticalHit), delay());
+        }
+        // A crit always breaks the guard, a heavy hitter only with a roll
+        if enemy.entity().defending
+            && (attack_roll == AttackRoll::Crit
+                || (self.can_break_guard() && game_rules.dice.roll_break_guard()))
+        {
+            enemy.entity_mut().defending = false;
+            reveal(text(Msg::GuardBroken), delay());
+        }
+        let (name, enemy_name) = (self.name().to_owned(), enemy.name().to_owned());
+        let enemy_entity = enemy.entity_mut();
+        let applied_dmg = enemy_entity.reduce_dmg(self_dmg);
+        // The overkill and the absorbed damage don't count, see `Entity::apply_dmg`
+        let dealt_dmg = applied_dmg
+            .saturating_sub(enemy_entity.shield)
+            .min(enemy_entity.life_points);
+        self.entity_mut().damage_dealt += dealt_dmg;
+        let self_entity = self.entity();
+        enemy_entity.combo = 0;
+        let defeated = if enemy_entity.apply_dmg(self_dmg) {
+            reveal(&msg(Msg::AttackDefeated, &[&name, &enemy_name]), delay());
+            game_rules.log(&self_entity.name, LogAction::Defeat, applied_dmg);
+            let xp = enemy_entity.xp_reward();
+            self.entity_mut().award_xp(xp);
+            true
+        } else {
+            reveal(&msg(Msg::AttackHit, &[&name, &applied_dmg]), delay());
+            game_rules.log(&self_entity.name, LogAction::Attack, applied_dmg);
+            if let Some(weapon) = &self_entity.weapon {
+                if weapon.poison > 0 {
+                    enemy_entity.poison(weapon.poison);
+                }
+                match weapon.enchantment {
+                    Some(Enchantment::Fire) => enemy_entity.burn(FIRE_BURN_DMG),
+                    Some(Enchantment::Frost) => {
+                        enemy_entity.frozen = true;
+                        reveal(&msg(Msg::Frozen, &[&enemy_name]), delay());
+                    }
+                    _ => {}
+                }
+            }
+            let frost = self_entity
+                .weapon
+                .as_ref()
+                .is_some_and(|w| w.enchantment == Some(Enchantment::Frost));
+            if (self.can_stun() || frost) && game_rules.dice.roll_stun() {
+                enemy_entity.stunned = true;
+                reveal(&msg(Msg::Stunned, &[&enemy_name]), delay());
+            }
+            false
+        };
+        let vampiric = self
+            .entity()
+            .weapon
+            .as_ref()
+            .is_some_and(|w| w.enchantment == Some(Enchantment::Vampiric));
+        if vampiric {
+            let entity = self.entity_mut();
+            let heal_lp = entity.heal(applied_dmg / VAMPIRIC_HEAL_DIVISOR);
+            reveal(&msg(Msg::LifeDrained, &[&name, &heal_lp]), delay());
+            game_rules.log(&entity.name, LogAction::Heal, heal_lp);
+        }
+        self.on_hit(applied_dmg);
+        self.entity_mut().wear_weapon();
+
+        // Only a surviving enemy reflects, so the attacker can't die after the enemy's defeat
+        let thorns = enemy.entity().thorns;
+        if !defeated && thorns > 0 && self.damage_type() == DamageType::Physical {
+            let reflected = applied_dmg.saturating_mul(thorns) / 100;
+            game_rules.log(&enemy.entity().name, LogAction::Thorns, reflected);
+            if self.entity_mut().apply_dmg(reflected) {
+                reveal(&msg(Msg::ThornsDefeated, &[&enemy_name, &name]), delay());
+                return true;
+            }
+            reveal(
+                &msg(Msg::Thorns, &[&enemy_name, &name, &reflected]),
+                delay(),
+            );
+        }
+
+        let stunned = enemy.entity().stunned;
+        if !defeated && !stunned && !game_rules.countering && game_rules.dice.roll_counter() {
+            reveal(&msg(Msg::Counter, &[&enemy.name()]), delay());
+            game_rules.countering = true;
+            let countered = enemy.attack(self, game_rules);
+            game_rules.countering = false;
+            return countered;
+        }
+        defeated
+    }
+
+    /// Attacks the `enemy` as the action of a turn (see `attack`). With an effective dexterity above
+    /// `EXTRA_ATTACK_DEXTERITY` the combatant attacks again as long as `Dice::roll_extra_attack` succeeds,
+    /// at most `MAX_EXTRA_ATTACKS` times, so harder difficulties make extra attacks rarer.
+    ///
+    /// Returns true if enemy is defeated or `self` was defeated!
+    fn attack_turn<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool
+    where
+        Self: Sized,
+    {
+        let mut ended = self.attack(enemy, game_rules);
+        let mut extra_attacks = 0;
+        while !ended
+            && extra_attacks < MAX_EXTRA_ATTACKS
+            && self.entity().effective_dexterity() > EXTRA_ATTACK_DEXTERITY
+            && game_rules.dice.roll_extra_attack()
+        {
+            extra_attacks += 1;
+            reveal(&msg(Msg::ExtraAttack, &[&self.name()]), delay());
+            ended = self.attack(enemy, game_rules);
+        }
+        ended
+    }
+
+    /// Fumbles an attack, which deals no damage and ends the combo.
+    /// On `Hard` the combatant hurts itself with the rolled `dmg` divided by `FUMBLE_SELF_DMG_DIVISOR`.
+    /// Returns true if the combatant defeated itself!
+    fn fumble(&mut self, dmg: usize, game_rules: &mut GameRules) -> bool {
+        let entity = self.entity_mut();
+        entity.combo = 0;
+        entity.wear_weapon();
+        reveal(&msg(Msg::Fumble, &[&self.name()]), delay());
+        game_rules.log(&self.entity().name, LogAction::Fumble, 0);
+        if game_rules.difficulty != Difficulty::Hard {
+            return false;
+        }
+        let self_dmg = dmg / FUMBLE_SELF_DMG_DIVISOR;
+        if self.entity_mut().apply_dmg(self_dmg) {
+            reveal(&msg(Msg::FumbleDefeated, &[&self.name()]), delay());
+            true
+        } else {
+            reveal(&msg(Msg::FumbleHurt, &[&self.name(), &self_dmg]), delay());
+            false
+        }
+    }
+
+    /// Called after every attack which hit the enemy with the applied `dmg`.
+    /// Defaults to doing nothing.
+    fn on_hit(&mut self, _dmg: usize) {}
+
+    /// Called at the start of every fight, before the fight order is determined.
+    /// Defaults to doing nothing.
+    fn start_fight(&mut self) {}
+
+    /// Called at the start of every round, before any action.
+    /// Defaults to doing nothing.
+    fn start_round(&mut self) {}
+
+    /// Returns true if the hits of the combatant may stun the enemy, see `attack`.
+    /// Defaults to false.
+    fn can_stun(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the hits of the combatant may break the guard of a defending enemy, see `attack`.
+    /// Defaults to false.
+    fn can_break_guard(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the combatant is able to taunt in party fights, which adds the taunt option.
+    /// Defaults to false.
+    fn can_taunt(&self) -> bool {
+        false
+    }
+
+    /// Draws the attacks of the enemy for `TAUNT_ROUNDS` rounds, only called in party fights if `can_taunt`.
+    fn taunt(&mut self, game_rules: &mut GameRules) {
+        let entity = self.entity_mut();
+        entity.taunt_turns = TAUNT_ROUNDS;
+        entity.combo = 0;
+        reveal(&msg(Msg::Taunted, &[&self.name()]), delay());
+        game_rules.log(&self.entity().name, LogAction::Taunt, 0);
+    }
+
+    /// Returns true if the combatant always attacks first in the opening round (e.g. a ranged opener).
+    /// Defaults to false, so the dexterity roll decides.
+    fn first_strike(&self) -> bool {
+        false
+    }
+
+    /// Halves the damage of the next incoming attack.
+    fn defend(&mut self, game_rules: &mut GameRules) {
+        let entity = self.entity_mut();
+        entity.defending = true;
+        entity.combo = 0;
+        reveal(&msg(Msg::Defends, &[&self.name()]), delay());
+        game_rules.log(&self.entity().name, LogAction::Defend, 0);
+    }
+
+    /// Drinks a potion, healing `POTION_HEAL` life points (capped at `max_life_points`).
+    fn use_potion(&mut self, game_rules: &mut GameRules) {
+        let entity = self.entity_mut();
+        entity.potions = entity.potions.saturating_sub(1);
+        entity.combo = 0;
+        let heal_lp = entity.heal(POTION_HEAL);
+        reveal(&msg(Msg::PotionUsed, &[&self.name(), &heal_lp]), delay());
+        game_rules.log(&self.entity().name, LogAction::Potion, heal_lp);
+    }
+
+    /// Drinks an antidote, curing every status (see `Entity::cleanse`).
+    fn use_antidote(&mut self, game_rules: &mut GameRules) {
+        let entity = self.entity_mut();
+        entity.antidotes = entity.antidotes.saturating_sub(1);
+        entity.combo = 0;
+        entity.cleanse();
+        reveal(&msg(Msg::AntidoteUsed, &[&self.name()]), delay());
+        game_rules.log(&self.entity().name, LogAction::Antidote, 0);
+    }
+
+    /// Lets the action source choose a weapon of the inventory and swaps it with the current one.
+    /// Returns false without swapping if the level is too low for the chosen weapon (see `Entity::can_wield`),
+    /// such weapons are marked with their level.
+    fn swap_weapon(&mut self, game_rules: &mut GameRules) -> bool {
+        let entity = self.entity();
+        let options: Vec<String> = entity
+            .inventory
+            .iter()
+            .map(|weapon| {
+                if entity.can_wield(weapon) {
+                    msg(Msg::WeaponOption, &[&weapon.calc_damage()])
+                } else {
+                    let level = weapon.min_level();
+                    msg(Msg::WeaponOptionLocked, &[&weapon.calc_damage(), &level])
+                }
+            })
+            .collect();
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        let i = game_rules.choose(text(Msg::SelectWeapon), &options);
+        let weapon = &self.entity().inventory[i];
+        if !self.entity().can_wield(weapon) {
+            let (material, level) = (&weapon.material, weapon.min_level());
+            reveal(
+                &msg(Msg::WeaponLocked, &[&self.name(), material, &level]),
+                delay(),
+            );
+            return false;
+        }
+        let entity = self.entity_mut();
+        entity.swap_weapon(i);
+        entity.combo = 0;
+        reveal(&msg(Msg::WeaponSwapped, &[&self.name()]), delay());
+        game_rules.log(&self.entity().name, LogAction::SwapWeapon, 0);
+        true
+    }
+
+    /// Tries to flee, which succeeds if the dice rolled `n`.
+    /// Returns true if fleeing was successful!
+    /// A carried weight may hold the combatant back anyway, with a chance of `Entity::flee_penalty`.
+    fn flee(&mut self, game_rules: &mut GameRules) -> bool {
+        self.entity_mut().combo = 0;
+        let penalty = self.entity().flee_penalty();
+        let success = game_rules.dice.throw_dice() && !game_rules.dice.roll_percent(penalty);
+        if success {
+            reveal(text(Msg::FleeSuccess), delay());
+            game_rules.log(&self.entity().name, LogAction::Flee, 0);
+        } else {
+            reveal(text(Msg::FleeFailure), delay());
+            game_rules.log(&self.entity().name, LogAction::FleeFailed, 0);
+            self.entity_mut().flee_cooldown = FLEE_COOLDOWN_ROUNDS;
+        }
+        success
+    }
+
+    /// Gets the flee option, showing the cooldown if fleeing isn't possible.
+    fn flee_option(&self, game_rules: &GameRules) -> String {
+        match self.entity().flee_cooldown {
+            0 => {
+                let penalty = self.entity().flee_penalty() as f64 / 100.0;
+                let percent = game_rules.dice.flee_probability() * (1.0 - penalty) * 100.0;
+                msg(Msg::FleeOption, &[&format!("{percent:.0}")])
+            }
+            cooldown => msg(Msg::FleeCooldownOption, &[&cooldown]),
+        }
+    }
+
+    /// Returns true if the combatant is able to heal itself, which adds the heal option.
+    /// Defaults to false.
+    fn can_heal(&self) -> bool {
+        false
+    }
+
+    /// Gets the heal option, only shown if `can_heal`.
+    fn heal_option(&self) -> String {
+        text(Msg::Heal).into()
+    }
+
+    /// Heals the combatant, only called if `can_heal`.
+    /// Returns false if healing wasn't possible, which doesn't consume the turn.
+    /// Defaults to not healing.
+    fn heal(&mut self, _game_rules: &mut GameRules) -> bool {
+        false
+    }
+
+    /// Selector for what the combatant want to do next.
+    /// Default is that the `Combatant` can either attack, heal (if `can_heal`), defend,
+    /// use a potion (if it has any), an antidote (if it has any and a status is active),
+    /// taunt (if `can_taunt` in a party fight),
+    /// swap the weapon (if it has spare ones) or flee!
+    ///
+    /// Returns `true` if the enemy is dead, `self` is dead or fleeing was successful!
+    fn select_action<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool
+    where
+        Self: Sized,
+    {
+        loop {
+            let attack_dmg = self.attack_damage();
+            let potions = self.entity().potions;
+            let mut options = vec![msg(Msg::AttackOption, &[&DamageRange::new(attack_dmg)])];
+            if self.can_heal() {
+                options.push(self.heal_option());
+            }
+            options.push(text(Msg::DefendOption).into());
+            if potions > 0 {
+                options.push(msg(Msg::PotionOption, &[&POTION_HEAL, &potions]));
+            }
+            let antidotes = self.entity().antidotes;
+            if antidotes > 0 && self.entity().has_status() {
+                options.push(msg(Msg::AntidoteOption, &[&antidotes]));
+            }
+            if self.can_taunt() && game_rules.party {
+                options.push(text(Msg::Taunt).into());
+            }
+            if !self.entity().inventory.is_empty() {
+                options.push(text(Msg::SwapWeapon).into());
+            }
+            options.push(self.flee_option(game_rules));
+            let options: Vec<&str> = options.iter().map(String::as_str).collect();
+            let i = game_rules.choose_action(self.entity(), &options);
+
+            return match options[i] {
+                option if option.starts_with(text(Msg::Attack)) => {
+                    self.attack_turn(enemy, game_rules)
+                }
+                option if option.starts_with(text(Msg::Heal)) => {
+                    if !self.heal(game_rules) {
+                        game_rules.actions.reject();
+                        continue;
+                    }
+                    self.entity_mut().combo = 0;
+                    false
+                }
+                option if option.starts_with(text(Msg::Defend)) => {
+                    self.defend(game_rules);
+                    false
+                }
+                option if option.starts_with(text(Msg::Potion)) => {
+                    self.use_potion(game_rules);
+                    false
+                }
+                option if option.starts_with(text(Msg::Antidote)) => {
+                    self.use_antidote(game_rules);
+                    false
+                }
+                option if option.starts_with(text(Msg::Taunt)) => {
+                    self.taunt(game_rules);
+                    false
+                }
+                option if option.starts_with(text(Msg::SwapWeapon)) => {
+                    if !self.swap_weapon(game_rules) {
+                        game_rules.actions.reject();
+                        continue;
+                    }
+                    false
+                }
+                option if option.starts_with(text(Msg::Flee)) => {
+                    if self.entity().flee_cooldown > 0 {
+                        game_rules.actions.reject();
+                        continue;
+                    }
+                    self.flee(game_rules)
+                }
+                _ => unimplemented!(),
+            };
+        }
+    }
+
+    /// Takes the turn of the combatant with `select_action`, unless it's stunned,
+    /// which skips the turn and ends the stun.
+    ///
+    /// Returns `true` if the enemy is dead, `self` is dead or fleeing was successful!
+    fn take_turn<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool
+    where
+        Self: Sized,
+    {
+        let entity = self.entity_mut();
+        if entity.stunned {
+            entity.stunned = false;
+            reveal(&msg(Msg::StunSkipped, &[&self.name()]), delay());
+            return false;
+        }
+        self.select_action(enemy, game_rules)
+    }
+
+    /// Simulates a fight against an `enemy` with a set of `game_rules`, revealing it on the terminal (see `reveal_fight`).
+    /// Runs until `self` or `enemy` is dead (has 0 `life_points`), fled or the `max_rounds` are reached.
+    ///
+    /// Returns how the fight ended, seen from `self` as the player.
+    fn fight<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> FightResult
+    where
+        Self: Sized,
+    {
+        reveal_fight(self.fight_rounds(enemy, game_rules))
+    }
+
+    /// Starts a fight against an `enemy` with a set of `game_rules`, which is fought step by step
+    /// by iterating the returned `FightRounds`, e.g. for a frontend showing every `FightEvent`.
+    /// The fight order is rolled right away.
+    fn fight_rounds<'a, E: Combatant>(
+        &'a mut self,
+        enemy: &'a mut E,
+        game_rules: &'a mut GameRules,
+    ) -> FightRounds<'a, Self, E>
+    where
+        Self: Sized,
+    {
+        self.start_fight();
+        enemy.start_fight();
+        self.warn_proficiency();
+        self.entity_mut().begin_fight();
+        enemy.entity_mut().begin_fight();
+
+        let ordering = roll_initiative(game_rules, self.entity(), enemy.entity());
+        // A first strike overrides the order of the opening round only
+        let first_ordering = if self.first_strike() {
+            Ordering::Player
+        } else {
+            ordering
+        };
+        FightRounds {
+            players: slice::from_mut(self),
+            enemy,
+            game_rules,
+            party: false,
+            fled: vec![false],
+            ordering,
+            first_ordering,
+            phase: Phase::Opening,
+            round: 0,
+            logged: 0,
+            outcome: None,
+        }
+    }
+}
+
+/// How a fight ended, seen from the initiator of the fight as the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FightOutcome {
+    PlayerWon,
+    EnemyWon,
+    PlayerFled,
+    EnemyFled,
+    /// Neither side was defeated within the `max_rounds` of the game rules
+    /// or both sides were defeated at the same time, e.g. by poison.
+    Draw,
+}
+
+impl FightOutcome {
+    /// Gets the outcome if a side is defeated, `Draw` if both are.
+    fn by_defeat(player: &Entity, enemy: &Entity) -> Option<Self> {
+        match (!player.is_alive(), !enemy.is_alive()) {
+            (true, true) => Some(Self::Draw),
+            (false, true) => Some(Self::PlayerWon),
+            (true, false) => Some(Self::EnemyWon),
+            (false, false) => None,
+        }
+    }
+
+    /// The player ended the fight, either by defeating the `enemy`,
+    /// being defeated by a counter or by fleeing.
+    fn ended_by_player(player: &Entity, enemy: &Entity) -> Self {
+        Self::by_defeat(player, enemy).unwrap_or(Self::PlayerFled)
+    }
+
+    /// The enemy ended the fight, either by defeating the `player`,
+    /// being defeated by a counter or by fleeing.
+    fn ended_by_enemy(player: &Entity, enemy: &Entity) -> Self {
+        Self::by_defeat(player, enemy).unwrap_or(Self::EnemyFled)
+    }
+}
+
+/// The result of a fight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FightResult {
+    pub outcome: FightOutcome,
+    /// Count of rounds elapsed.
+    pub rounds: usize,
+}
+
+/// A fight step by step, see `Combatant::fight_rounds` and `fight_party_rounds`. Every step is a `FightEvent`,
+/// so a frontend can show them, and the iteration ends with the fight, after which `result` tells how it ended.
+/// The actions of the combatants still reveal their own messages, see `OutputSink` to redirect them.
+pub struct FightRounds<'a, P: Combatant, E: Combatant> {
+    /// The player, or every member of a party.
+    players: &'a mut [P],
+    enemy: &'a mut E,
+    game_rules: &'a mut GameRules,
+    /// Whether the `players` fight as a party, see `fight_party`.
+    party: bool,
+    /// Whether each of the `players` fled, which don't take any turns anymore.
+    fled: Vec<bool>,
+    /// The rolled fight order, see `reroll_initiative` of the game rules. A party always goes first.
+    ordering: Ordering,
+    /// The fight order of the opening round, which a first strike overrides.
+    first_ordering: Ordering,
+    /// The next step of the fight.
+    phase: Phase,
+    /// Count of rounds elapsed.
+    round: usize,
+    /// Length of the combat log at the start of the round, the actions of the round follow it.
+    logged: usize,
+    outcome: Option<FightOutcome>,
+}
+
+/// The next step of the `FightRounds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Telling the fight order of the opening round.
+    Opening,
+    /// Starting the next round, unless the `max_rounds` are reached.
+    RoundStart,
+    /// Ticking the statuses at the start of the round.
+    Statuses,
+    /// Rolling the fight order again with `reroll_initiative`, before the turns of a single player.
+    Initiative,
+    /// The turns of a single player and the enemy.
+    Turns,
+    /// Telling the status of the first member of a party in the fight from this one on.
+    MemberStatus(usize),
+    /// The turn of this member of a party.
+    MemberTurn(usize),
+    /// The turn of the enemy of a party, after every member.
+    EnemyTurn,
+    Ended,
+}
+
+impl<P: Combatant, E: Combatant> FightRounds<'_, P, E> {
+    /// Gets the result of the fight, `None` while it goes on.
+    pub fn result(&self) -> Option<FightResult> {
+        self.outcome.map(|outcome| FightResult {
+            outcome,
+            rounds: self.round,
+        })
+    }
+
+    /// Gets the player, or every member of a party.
+    pub fn players(&self) -> &[P] {
+        self.players
+    }
+
+    pub fn enemy(&self) -> &E {
+        self.enemy
+    }
+
+    /// Ends the round (with the `outcome` if any), notifying the observer and saving the game.
+    fn end_round(&mut self, outcome: Option<FightOutcome>) -> FightEvent {
+        let players: Vec<&Entity> = self.players.iter().map(|p| p.entity()).collect();
+        let game_rules = &mut *self.game_rules;
+        game_rules
+            .observer
+            .round_end(self.round, &players, self.enemy.entity());
+        game_rules.autosave();
+        game_rules.check_interrupt();
+        let event = RoundEvent {
+            round: self.round,
+            actions: game_rules.log[self.logged..].to_vec(),
+            player_life_points: players.iter().map(|p| p.life_points).collect(),
+            enemy_life_points: self.enemy.entity().life_points,
+            outcome,
+        };
+        match outcome {
+            Some(outcome) => self.end(outcome),
+            None => self.phase = Phase::RoundStart,
+        }
+        FightEvent::RoundEnd(event)
+    }
+
+    /// Ends the fight with the `outcome`, adapting a dynamic difficulty.
+    fn end(&mut self, outcome: FightOutcome) {
+        self.outcome = Some(outcome);
+        self.phase = Phase::Ended;
+        self.game_rules.party = false;
+        let players: Vec<&Entity> = self.players.iter().map(|p| p.entity()).collect();
+        self.game_rules.adapt_difficulty(outcome, &players);
+    }
+
+    /// Takes the next step of the fight, `None` if it has nothing to tell.
+    fn step(&mut self) -> Option<FightEvent> {
+        let (players, enemy, game_rules) =
+            (&mut *self.players, &mut *self.enemy, &mut *self.game_rules);
+        match self.phase {
+            Phase::Opening => {
+                self.phase = Phase::RoundStart;
+                Some(FightEvent::Initiative(self.first_ordering))
+            }
+            Phase::RoundStart => {
+                if game_rules.reached_max_rounds(self.round) {
+                    self.end(FightOutcome::Draw);
+                    return None;
+                }
+                self.round += 1;
+                game_rules.round = self.round;
+                self.logged = game_rules.log.len();
+                self.phase = Phase::Statuses;
+                Some(FightEvent::RoundStart(self.round))
+            }
+            Phase::Statuses => {
+                for player in players.iter_mut() {
+                    player.start_round();
+                    player.entity_mut().begin_round(game_rules);
+                }
+                enemy.start_round();
+                enemy.entity_mut().begin_round(game_rules);
+                let entities: Vec<&Entity> = players.iter().map(|p| p.entity()).collect();
+                game_rules
+                    .observer
+                    .round_start(self.round, &entities, enemy.entity());
+
+                // Poison and burn tick at the start of every round, on both sides before a defeat counts
+                if self.party {
+                    for (member, fled) in players.iter_mut().zip(&self.fled) {
+                        if !fled && member.is_alive() {
+                            member.entity_mut().tick_statuses(game_rules);
+                        }
+                    }
+                    if enemy.entity_mut().tick_statuses(game_rules) {
+                        // Defeated at the same time as the whole party
+                        let alive = players.iter().any(|member| member.is_alive());
+                        return Some(self.end_round(Some(if alive {
+                            FightOutcome::PlayerWon
+                        } else {
+                            FightOutcome::Draw
+                        })));
+                    }
+                    self.phase = Phase::MemberStatus(0);
+                    return None;
+                }
+                let player = &mut players[0];
+                let player_poisoned = player.entity_mut().tick_statuses(game_rules);
+                let enemy_poisoned = enemy.entity_mut().tick_statuses(game_rules);
+                if player_poisoned || enemy_poisoned {
+                    let outcome = FightOutcome::by_defeat(player.entity(), enemy.entity());
+                    return Some(self.end_round(outcome));
+                }
+                self.phase = Phase::Initiative;
+                Some(FightEvent::Status(0))
+            }
+            Phase::Initiative => {
+                self.phase = Phase::Turns;
+                // With `reroll_initiative` the order is rolled again every round after the opening one
+                if self.round == 1 || !game_rules.reroll_initiative {
+                    return None;
+                }
+                self.ordering = roll_initiative(game_rules, players[0].entity(), enemy.entity());
+                Some(FightEvent::Initiative(self.ordering))
+            }
+            Phase::Turns => {
+                let player = &mut players[0];
+                let ordering = if self.round == 1 {
+                    self.first_ordering
+                } else {
+                    self.ordering
+                };
+                let by_player = |player: &P, enemy: &E| {
+                    FightOutcome::ended_by_player(player.entity(), enemy.entity())
+                };
+                let by_enemy = |player: &P, enemy: &E| {
+                    FightOutcome::ended_by_enemy(player.entity(), enemy.entity())
+                };
+                let ended = match ordering {
+                    Ordering::Player => {
+                        if player.take_turn(enemy, game_rules) {
+                            Some(by_player(player, enemy))
+                        } else if enemy.take_turn(player, game_rules) {
+                            Some(by_enemy(player, enemy))
+                        } else {
+                            None
+                        }
+                    }
+                    Ordering::Enemy => {
+                        if enemy.take_turn(player, game_rules) {
+                            Some(by_enemy(player, enemy))
+                        } else if player.take_turn(enemy, game_rules) {
+                            Some(by_player(player, enemy))
+                        } else {
+                            None
+                        }
+                    }
+                };
+                Some(self.end_round(ended))
+            }
+            Phase::MemberStatus(from) => {
+                let next = (from..players.len()).find(|&i| !self.fled[i] && players[i].is_alive());
+                self.phase = match next {
+                    Some(i) => Phase::MemberTurn(i),
+                    None => Phase::EnemyTurn,
+                };
+                next.map(FightEvent::Status)
+            }
+            Phase::MemberTurn(i) => {
+                let member = &mut players[i];
+                // Either the enemy or the member is dead, or the member fled
+                if member.take_turn(enemy, game_rules) {
+                    if !enemy.is_alive() {
+                        return Some(self.end_round(Some(FightOutcome::PlayerWon)));
+                    }
+                    self.fled[i] = member.is_alive();
+                }
+                self.phase = Phase::MemberStatus(i + 1);
+                None
+            }
+            Phase::EnemyTurn => {
+                if !enemy.is_alive() {
+                    return Some(self.end_round(Some(FightOutcome::PlayerWon)));
+                }
+                // The enemy attacks the first member in the fight, preferring a taunting one
+                let target = players
+                    .iter_mut()
+                    .zip(&self.fled)
+                    .filter(|(member, fled)| !**fled && member.is_alive())
+                    .min_by_key(|(member, _)| member.entity().taunt_turns == 0);
+                if let Some((member, _)) = target {
+                    enemy.take_turn(member, game_rules);
+                    if !enemy.is_alive() {
+                        return Some(self.end_round(Some(FightOutcome::PlayerWon)));
+                    }
+                }
+                let in_fight = players
+                    .iter()
+                    .zip(&self.fled)
+                    .any(|(member, fled)| !fled && member.is_alive());
+                let ended = (!in_fight).then(|| {
+                    if self.fled.contains(&true) {
+                        FightOutcome::PlayerFled
+                    } else {
+                        FightOutcome::EnemyWon
+                    }
+                });
+                Some(self.end_round(ended))
+            }
+            Phase::Ended => None,
+        }
+    }
+}
+
+impl<P: Combatant, E: Combatant> Iterator for FightRounds<'_, P, E> {
+    type Item = FightEvent;
+
+    /// Fights until the next event, `None` if the fight ended already or reached the `max_rounds`.
+    fn next(&mut self) -> Option<FightEvent> {
+        while self.phase != Phase::Ended {
+            if let Some(event) = self.step() {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+/// What happens in a fight, in order, see `FightRounds`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FightEvent {
+    /// Who attacks first: Told at the start of a single fight and every further round with `reroll_initiative`.
+    Initiative(Ordering),
+    /// The round starts, before the statuses tick.
+    RoundStart(usize),
+    /// The player (the member `i` of a party) is about to take the turn, e.g. to show the life points.
+    Status(usize),
+    /// The round ended, with everything that happened in it.
+    RoundEnd(RoundEvent),
+}
+
+/// What happened in a round of a fight, see `FightEvent::RoundEnd`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundEvent {
+    pub round: usize,
+    /// Who acted and what happened in order, as recorded in the combat log.
+    pub actions: Vec<LogEntry>,
+    /// Life points of the player (every member of a party) after the round.
+    pub player_life_points: Vec<usize>,
+    /// Life points of the enemy after the round.
+    pub enemy_life_points: usize,
+    /// How the fight ended in this round, `None` if it goes on.
+    pub outcome: Option<FightOutcome>,
+}
+
+/// Fights the `rounds` to the end and reveals their events on the terminal: The fight order,
+/// the start of every round and the life points before the turns. This is the terminal frontend of `FightRounds`.
+///
+/// Returns how the fight ended.
+fn reveal_fight<P: Combatant, E: Combatant>(mut rounds: FightRounds<'_, P, E>) -> FightResult {
+    while let Some(event) = rounds.next() {
+        match event {
+            FightEvent::Initiative(ordering) => reveal(
+                &msg(Msg::FirstAttacker, &[&format!("{ordering:?}")]),
+                delay(),
+            ),
+            FightEvent::RoundStart(round) => reveal(&msg(Msg::RoundStart, &[&round]), delay()),
+            FightEvent::Status(i) => {
+                let (player, enemy) = (rounds.players()[i].entity(), rounds.enemy().entity());
+                reveal(
+                    &msg(
+                        Msg::RoundStatus,
+                        &[
+                            &rounds.players()[i].name(),
+                            &render_healthbar(player.life_points, player.max_life_points),
+                            &rounds.enemy().name(),
+                            &render_healthbar(enemy.life_points, enemy.max_life_points),
+                        ],
+                    ),
+                    delay(),
+                );
+            }
+            FightEvent::RoundEnd(_) => {}
+        }
+    }
+    rounds
+        .result()
+        .expect("Expected the fight to end after the last round")
+}
+
+/// Renders the life points as a bar of `HEALTHBAR_WIDTH` with the numbers, e.g. `[#####-----] 50/100`.
+pub fn render_healthbar(current: usize, max: usize) -> String {
+    let filled = (current.min(max) * HEALTHBAR_WIDTH)
+        .checked_div(max)
+        .unwrap_or(0);
+    format!(
+        "[{}{}] {current}/{max}",
+        "#".repeat(filled),
+        "-".repeat(HEALTHBAR_WIDTH - filled)
+    )
+}
+
+/// Simulates a fight of a `party` against an `enemy` with a set of `game_rules`, revealing it on the terminal (see `reveal_fight`).
+/// Every round the members take their turns in order, then the `enemy` attacks the first member in the fight,
+/// preferring a taunting one (see `Combatant::taunt`). Dead or fled members are skipped.
+///
+/// Runs until the `enemy` is dead, no member is in the fight anymore or the `max_rounds` are reached.
+/// If at least one member fled and survived the fight counts as fled, otherwise as lost.
+pub fn fight_party<C: Combatant, E: Combatant>(
+    party: &mut [C],
+    enemy: &mut E,
+    game_rules: &mut GameRules,
+) -> FightResult {
+    reveal_fight(fight_party_rounds(party, enemy, game_rules))
+}
+
+/// Starts a fight of a `party` against an `enemy` (see `fight_party`), which is fought step by step
+/// by iterating the returned `FightRounds` like `Combatant::fight_rounds`.
+pub fn fight_party_rounds<'a, C: Combatant, E: Combatant>(
+    party: &'a mut [C],
+    enemy: &'a mut E,
+    game_rules: &'a mut GameRules,
+) -> FightRounds<'a, C, E> {
+    for member in party.iter_mut() {
+        member.start_fight();
+        member.warn_proficiency();
+        member.entity_mut().begin_fight();
+    }
+    enemy.start_fight();
+    enemy.entity_mut().begin_fight();
+    game_rules.party = true;
+    FightRounds {
+        fled: vec![false; party.len()],
+        players: party,
+        enemy,
+        game_rules,
+        party: true,
+        ordering: Ordering::Player,
+        first_ordering: Ordering::Player,
+        phase: Phase::RoundStart,
+        round: 0,
+        logged: 0,
+        outcome: None,
+    }
+}
+
+/// Called with the game at the end of an interrupted round, see `GameRules::set_interrupt`.
+type InterruptHandler = Box<dyn FnMut(Option<Replay>)>;
+
+/// General Game Rules.
+pub struct GameRules {
+    dice: Dice,
+    /// Source of the chosen actions, the terminal by default.
+    actions: Box<dyn ActionSource>,
+    /// Observer of every round, none by default.
+    observer: Box<dyn FightObserver>,
+    /// The current round of the fight.
+    round: usize,
+    /// Every event of the fights so far.
+    log: Vec<LogEntry>,
+    /// Whether a counterattack is in progress, which can't be countered again.
+    countering: bool,
+    /// Whether a party fight is in progress, in which taunting is possible.
+    party: bool,
+    /// Every chosen action index so far, e.g. for a replay.
+    choices: Vec<usize>,
+    /// Count of rounds after which a fight ends in a draw.
+    max_rounds: usize,
+    difficulty: Difficulty,
+    /// The seed of the dice, if seeded.
+    seed: Option<u64>,
+    /// File the game is saved to after every round, see `autosave`.
+    autosave: Option<PathBuf>,
+    /// The last error of saving to the autosave file, see `take_autosave_error`.
+    autosave_error: Option<io::Error>,
+    /// Flag set from another thread (e.g. on Ctrl-C) and the handler called after the round, see `set_interrupt`.
+    interrupt: Option<(Arc<AtomicBool>, InterruptHandler)>,
+    /// The config the game started with as json, see `set_config`.
+    config: Option<serde_json::Value>,
+    /// Whether the fight order is rolled every round instead of once per fight.
+    reroll_initiative: bool,
+    /// Who gets the first turn if the rolled dexterity equals the one of the enemy, see `TieBreak`.
+    tie_break: TieBreak,
+}
+
+impl GameRules {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self::from_dice(Dice::new(difficulty.to_dice_n()), difficulty, None)
+    }
+
+    /// Same as `new` but with a seeded dice, so every roll (and therefore the whole fight) is reproducible.
+    pub fn with_seed(difficulty: Difficulty, seed: u64) -> Self {
+        Self::from_dice(
+            Dice::with_seed(difficulty.to_dice_n(), seed),
+            difficulty,
+            Some(seed),
+        )
+    }
+
+    /// Same as `new` but the dice rolls with `rng`, e.g. a `FixedSequence` for preset rolls.
+    pub fn with_random(difficulty: Difficulty, rng: impl RandomSource + 'static) -> Self {
+        Self::from_dice(
+            Dice::with_random(difficulty.to_dice_n(), rng),
+            difficulty,
+            None,
+        )
+    }
+
+    fn from_dice(dice: Dice, difficulty: Difficulty, seed: Option<u64>) -> Self {
+        Self {
+            dice,
+            actions: Box::new(TerminalActions),
+            observer: Box::new(NoObserver),
+            round: 0,
+            log: vec![],
+            countering: false,
+            party: false,
+            choices: vec![],
+            max_rounds: DEFAULT_MAX_ROUNDS,
+            difficulty,
+            seed,
+            autosave: None,
+            autosave_error: None,
+            interrupt: None,
+            config: None,
+            reroll_initiative: false,
+            tie_break: TieBreak::default(),
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Gets the seed of the dice, `None` if it isn't seeded.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Saves the game as a `Replay` to `path` after every round, so it can be resumed.
+    /// Only works with a seeded dice.
+    pub fn set_autosave(&mut self, path: PathBuf) {
+        self.autosave = Some(path);
+    }
+
+    /// Takes the last error of saving to the autosave file (see `set_autosave`), so the caller can report it.
+    pub fn take_autosave_error(&mut self) -> Option<io::Error> {
+        self.autosave_error.take()
+    }
+
+    /// Calls `on_interrupt` with the game as a `Replay` (`None` without a seeded dice) at the end of every round
+    /// while the `interrupted` flag is set, e.g. by a Ctrl-C handler to save and quit.
+    /// So the game is only interrupted between the rounds, on the thread of the game.
+    pub fn set_interrupt(
+        &mut self,
+        interrupted: Arc<AtomicBool>,
+        on_interrupt: impl FnMut(Option<Replay>) + 'static,
+    ) {
+        self.interrupt = Some((interrupted, Box::new(on_interrupt)));
+    }
+
+    /// Saves the game to the autosave file, if set. A failure is kept, see `take_autosave_error`.
+    fn autosave(&mut self) {
+        let Some(path) = &self.autosave else {
+            return;
+        };
+        let Some(replay) = Replay::record(self) else {
+            return;
+        };
+        if let Err(e) = replay.save_to_file(path) {
+            self.autosave_error = Some(e);
+        }
+    }
+
+    /// Calls the interrupt handler if the interrupt flag is set, see `set_interrupt`.
+    fn check_interrupt(&mut self) {
+        let Some((interrupted, mut on_interrupt)) = self.interrupt.take() else {
+            return;
+        };
+        if interrupted.load(atomic::Ordering::SeqCst) {
+            on_interrupt(Replay::record(self));
+        }
+        self.interrupt = Some((interrupted, on_interrupt));
+    }
+
+    /// Records an event of the current round into the combat log.
+    pub fn log(&mut self, actor: &str, action: LogAction, amount: usize) {
+        self.log.push(LogEntry {
+            round: self.round,
+            actor: actor.into(),
+            action,
+            amount,
+        });
+    }
+
+    /// Gets every recorded event in order.
+    pub fn log_entries(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    /// Serializes the combat log to json.
+    pub fn log_to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.log)
+    }
+
+    /// Replaces the source of the chosen actions, e.g. with `ScriptedActions` for headless fights.
+    pub fn set_actions(&mut self, actions: impl ActionSource + 'static) {
+        self.actions = Box::new(actions);
+    }
+
+    /// Sets the count of rounds after which a fight ends in a draw, `DEFAULT_MAX_ROUNDS` by default.
+    pub fn set_max_rounds(&mut self, max_rounds: usize) {
+        self.max_rounds = max_rounds;
+    }
+
+    /// Gets the count of rounds after which a fight ends in a draw.
+    pub fn max_rounds(&self) -> usize {
+        self.max_rounds
+    }
+
+    /// Returns true (and reveals it) if the fight reached `max_rounds` after `round` rounds.
+    fn reached_max_rounds(&self, round: usize) -> bool {
+        let reached = round >= self.max_rounds;
+        if reached {
+            reveal(&msg(Msg::Draw, &[&self.max_rounds]), delay());
+        }
+        reached
+    }
+
+    /// Sets whether the fight order is rolled every round (see `roll_initiative`), once per fight by default.
+    pub fn set_reroll_initiative(&mut self, reroll_initiative: bool) {
+        self.reroll_initiative = reroll_initiative;
+    }
+
+    /// Gets whether the fight order is rolled every round.
+    pub fn reroll_initiative(&self) -> bool {
+        self.reroll_initiative
+    }
+
+    /// Sets who gets the first turn if the rolled dexterity of the player equals the one of the enemy,
+    /// the defender (the enemy) by default.
+    pub fn set_tie_break(&mut self, tie_break: TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    pub fn tie_break(&self) -> TieBreak {
+        self.tie_break
+    }
+
+    /// Sets the count of dice rolled per roll and how they're combined, a single die by default.
+    pub fn set_dice_pool(&mut self, pool: DicePool) {
+        self.dice.pool = pool;
+    }
+
+    pub fn dice_pool(&self) -> DicePool {
+        self.dice.pool
+    }
+
+    /// Gets the current count of dice sides, which only changes with a `Dynamic` difficulty.
+    pub fn dice_sides(&self) -> usize {
+        self.dice.n
+    }
+
+    /// Adapts a `Dynamic` difficulty to the `outcome` of the last fight of the `players`:
+    /// A dice side more after a win with at least `DYNAMIC_DOMINANT_PERCENT` of the life points left,
+    /// a side less after a win with at most `DYNAMIC_BARELY_PERCENT` left or a defeat,
+    /// within `DYNAMIC_DICE_SIDES`. Other difficulties stay the same.
+    fn adapt_difficulty(&mut self, outcome: FightOutcome, players: &[&Entity]) {
+        if self.difficulty != Difficulty::Dynamic {
+            return;
+        }
+        let life_points: usize = players.iter().map(|p| p.life_points).sum();
+        let max_life_points: usize = players.iter().map(|p| p.max_life_points).sum();
+        let percent = life_points.saturating_mul(100) / max_life_points.max(1);
+        let n = match outcome {
+            FightOutcome::PlayerWon if percent >= DYNAMIC_DOMINANT_PERCENT => self.dice.n + 1,
+            FightOutcome::PlayerWon if percent <= DYNAMIC_BARELY_PERCENT => self.dice.n - 1,
+            FightOutcome::EnemyWon => self.dice.n - 1,
+            _ => self.dice.n,
+        };
+        let n = n.clamp(*DYNAMIC_DICE_SIDES.start(), *DYNAMIC_DICE_SIDES.end());
+        if n != self.dice.n {
+            self.dice.n = n;
+            reveal(&msg(Msg::DifficultyAdapted, &[&n]), delay());
+        }
+    }
+
+    /// Replaces the observer of every round, e.g. for animations or logging.
+    pub fn set_observer(&mut self, observer: impl FightObserver + 'static) {
+        self.observer = Box::new(observer);
+    }
+
+    /// Lets the action source choose the next action of `entity` and records the choice.
+    fn choose_action(&mut self, entity: &Entity, options: &[&str]) -> usize {
+        let i = self.actions.choose_action(entity, options);
+        self.choices.push(i);
+        i
+    }
+
+    /// Lets the action source choose one of the `options` (e.g. a weapon) and records the choice.
+    fn choose(&mut self, prompt: &str, options: &[&str]) -> usize {
+        let i = self.actions.choose(prompt, options);
+        self.choices.push(i);
+        i
+    }
+
+    /// Lets the action source answer the `prompt` with yes or no and records the choice.
+    /// Returns true for yes!
+    pub fn confirm(&mut self, prompt: &str) -> bool {
+        self.choose(prompt, &[text(Msg::Yes), text(Msg::No)]) == 0
+    }
+
+    /// Gets every chosen action index in order.
+    pub fn choices(&self) -> &[usize] {
+        &self.choices
+    }
+
+    /// Sets the `config` the game started with (or its json), which is recorded in replays and saves
+    /// to check it when they are re-run (see `Replay::matches`).
+    pub fn set_config(&mut self, config: &impl Serialize) {
+        self.config = serde_json::to_value(config).ok();
+    }
+
+    /// Gets the config the game started with as json, if set.
+    pub fn config(&self) -> Option<&serde_json::Value> {
+        self.config.as_ref()
+    }
+
+    /// Reveals the raw dice rolls (e.g. "Würfel: 4/6") in addition to the normal messages,
+    /// e.g. for debugging the balance. Off by default.
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.dice.verbose = verbose;
+    }
+
+    pub fn verbose(&self) -> bool {
+        self.dice.verbose
+    }
+}
+
+/// The settings of `GameRules` besides the seed, shared by the serialized game rules and a `Replay`.
+/// The live state like the rng of the dice or the action source isn't part of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSettings {
+    pub difficulty: Difficulty,
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: usize,
+    #[serde(default)]
+    pub reroll_initiative: bool,
+    #[serde(default)]
+    pub dice_pool: DicePool,
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// The config the game started with as json, if it was set in the game rules (see `GameRules::set_config`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
+}
+
+fn default_max_rounds() -> usize {
+    DEFAULT_MAX_ROUNDS
+}
+
+impl RuleSettings {
+    /// Gets the settings of `game_rules`.
+    pub fn of(game_rules: &GameRules) -> Self {
+        Self {
+            difficulty: game_rules.difficulty,
+            max_rounds: game_rules.max_rounds,
+            reroll_initiative: game_rules.reroll_initiative,
+            dice_pool: game_rules.dice_pool(),
+            tie_break: game_rules.tie_break,
+            config: game_rules.config.clone(),
+        }
+    }
+
+    /// Creates new game rules with these settings and a dice seeded by `seed` (if any).
+    pub fn game_rules(&self, seed: Option<u64>) -> GameRules {
+        let mut game_rules = match seed {
+            Some(seed) => GameRules::with_seed(self.difficulty, seed),
+            None => GameRules::new(self.difficulty),
+        };
+        game_rules.set_max_rounds(self.max_rounds);
+        game_rules.set_reroll_initiative(self.reroll_initiative);
+        game_rules.set_dice_pool(self.dice_pool);
+        game_rules.set_tie_break(self.tie_break);
+        game_rules.config = self.config.clone();
+        game_rules
+    }
+}
+
+/// The serialized `GameRules`: The settings with the seed and the current dice sides.
+#[derive(Serialize, Deserialize)]
+struct SavedGameRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    /// The dice sides adapted by a `Dynamic` difficulty, the ones of the difficulty if missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dice_sides: Option<usize>,
+    #[serde(flatten)]
+    settings: RuleSettings,
+}
+
+/// Serializes the difficulty, the seed, the dice sides and the other settings, e.g. to save them alongside the config.
+/// The rng of the dice, the action source, the observer and the fights so far aren't serialized.
+impl Serialize for GameRules {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SavedGameRules {
+            seed: self.seed,
+            dice_sides: Some(self.dice_sides()),
+            settings: RuleSettings::of(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes the settings into new game rules, with a dice seeded by the saved seed (if any).
+impl<'de> Deserialize<'de> for GameRules {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let saved = SavedGameRules::deserialize(deserializer)?;
+        let mut game_rules = saved.settings.game_rules(saved.seed);
+        if let Some(sides) = saved.dice_sides {
+            game_rules.dice.n = sides.max(MIN_DICE_SIDES);
+        }
+        Ok(game_rules)
+    }
+}
+
+/// An event of a fight in the combat log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub round: usize,
+    /// Name of the acting entity.
+    pub actor: String,
+    pub action: LogAction,
+    /// Damage or healed life points, `0` if the action has none.
+    pub amount: usize,
+}
+
+/// The kind of an event in the combat log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogAction {
+    Attack,
+    Defeat,
+    Dodge,
+    Defend,
+    Heal,
+    Potion,
+    Antidote,
+    Flee,
+    FleeFailed,
+    Fumble,
+    Poison,
+    Burn,
+    Thorns,
+    SwapWeapon,
+    Taunt,
+}
+
+/// Who gets the first turn if the rolled dexterity of the initiator (the player) equals the one of the defender (the enemy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// The initiator of the fight attacks first.
+    Initiator,
+    /// The defender attacks first.
+    #[default]
+    Defender,
+    /// The initiative is rolled again, up to `MAX_INITIATIVE_REROLLS` times before the defender attacks first.
+    Reroll,
+}
+
+/// Parses `initiator`, `defender` or `reroll` (ignoring the case).
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "initiator" => Ok(Self::Initiator),
+            "defender" => Ok(Self::Defender),
+            "reroll" => Ok(Self::Reroll),
+            _ => Err(format!(
+                "Unknown tie-break `{s}`, expected `initiator`, `defender` or `reroll`"
+            )),
+        }
+    }
+}
+
+/// How the dice of a `DicePool` are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolMode {
+    /// Sums the dice, so middle results get likelier.
+    #[default]
+    Sum,
+    /// Takes the highest die, so high results get likelier (an advantage).
+    Max,
+}
+
+/// The count of dice rolled per roll and how they're combined. A single die by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DicePool {
+    pub count: usize,
+    pub mode: PoolMode,
+}
+
+impl Default for DicePool {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            mode: PoolMode::Sum,
+        }
+    }
+}
+
+/// Dice with `n` sides.
+///
+/// In rust, there are no random functions in it's `std`-library.
+/// Therefore using the `rngs`-crate for that, behind a `RandomSource`!
+pub struct Dice {
+    n: usize,
+    /// The dice rolled per roll, see `roll`.
+    pool: DicePool,
+    rng: Box<dyn RandomSource>,
+    /// Whether the raw rolls are revealed, see `GameRules::set_verbose`.
+    verbose: bool,
+}
+
+impl Dice {
+    pub fn new(n: usize) -> Self {
+        Self::new_pool(n, 1)
+    }
+
+    /// Creates a pool of `count` summed dice, see `DicePool`.
+    pub fn new_pool(n: usize, count: usize) -> Self {
+        Self {
+            pool: DicePool {
+                count,
+                mode: PoolMode::Sum,
+            },
+            ..Self::with_random(n, SmallRng::from_os_rng())
+        }
+    }
+
+    /// Creates a dice with a fixed `seed`. The same seed always produces the same sequence of rolls.
+    pub fn with_seed(n: usize, seed: u64) -> Self {
+        Self::with_random(n, SmallRng::seed_from_u64(seed))
+    }
+
+    /// Creates a dice rolling with `rng`, e.g. a `FixedSequence` for preset rolls.
+    pub fn with_random(n: usize, rng: impl RandomSource + 'static) -> Self {
+        Self {
+            n,
+            pool: DicePool::default(),
+            rng: Box::new(rng),
+            verbose: false,
+        }
+    }
+
+    /// Rolls every die of the pool and combines them (see `PoolMode`) to a result between 1 and `sides`,
+    /// revealing it in verbose mode.
+    fn roll(&mut self) -> usize {
+        let n = self.n;
+        let count = self.pool.count.max(1);
+        let rolls = (0..count).map(|_| self.rng.gen_range(1, n));
+        let roll = match self.pool.mode {
+            PoolMode::Sum => rolls.sum(),
+            PoolMode::Max => rolls.max().unwrap_or(1),
+        };
+        if self.verbose {
+            reveal(&msg(Msg::DiceRoll, &[&roll, &self.sides()]), delay());
+        }
+        roll
+    }
+
+    /// Gets the highest result of a roll: `n` times the count of dice for a summed pool, otherwise `n`.
+    fn sides(&self) -> usize {
+        match self.pool.mode {
+            PoolMode::Sum => self.n.saturating_mul(self.pool.count.max(1)),
+            PoolMode::Max => self.n,
+        }
+    }
+
+    /// Gets the lowest result of a roll: The count of dice for a summed pool, otherwise `1`.
+    fn lowest(&self) -> usize {
+        match self.pool.mode {
+            PoolMode::Sum => self.pool.count.max(1),
+            PoolMode::Max => 1,
+        }
+    }
+
+    /// Applys a dice roll to a number by calculating
+    /// `(roll / sides) * num` and returning the result.
+    pub fn apply_dice_roll(&mut self, num: usize) -> usize {
+        let roll = self.roll();
+        apply_roll(roll, self.sides(), num)
+    }
+
+    /// Returns true if the dice rolled the highest result, `n` for a single die.
+    pub fn throw_dice(&mut self) -> bool {
+        self.roll() == self.sides()
+    }
+
+    /// Gets the chance of fleeing successfully, which is the chance of rolling the highest result (see `throw_dice`):
+    /// `1/n` for a single die, every die showing `n` for a summed pool and any die showing `n` for a max pool.
+    pub fn flee_probability(&self) -> f64 {
+        let single = 1.0 / self.n as f64;
+        let count = i32::try_from(self.pool.count.max(1)).unwrap_or(i32::MAX);
+        match self.pool.mode {
+            PoolMode::Sum => single.powi(count),
+            PoolMode::Max => 1.0 - (1.0 - single).powi(count),
+        }
+    }
+
+    /// Gets the mean of `samples` rolls applied to `num` (see `apply_dice_roll`), e.g. for balancing.
+    /// The rolls are made with the rng of the dice, so a seeded dice always gets the same mean.
+    pub fn expected_value(&mut self, num: usize, samples: usize) -> f64 {
+        let sum: usize = (0..samples).map(|_| self.apply_dice_roll(num)).sum();
+        sum as f64 / samples.max(1) as f64
+    }
+
+    /// Gets the share of `samples` successful throws (see `throw_dice`), which approaches `flee_probability`.
+    pub fn flee_expected(&mut self, samples: usize) -> f64 {
+        let successes = (0..samples).filter(|_| self.throw_dice()).count();
+        successes as f64 / samples.max(1) as f64
+    }
+
+    /// Returns true with a chance of `percent`, always false for `0`.
+    pub fn roll_percent(&mut self, percent: usize) -> bool {
+        percent > 0 && self.rng.gen_range(1, 100) <= percent
+    }
+
+    /// Rolls the damage of an attack within the `DamageRange` of the `base` damage.
+    pub fn roll_damage(&mut self, base: usize) -> usize {
+        let range = DamageRange::new(base);
+        self.rng.gen_range(range.min, range.max)
+    }
+
+    /// Picks an index of `weights` with a chance proportional to its weight.
+    /// Returns `None` if all weights are 0 or their sum overflows.
+    pub fn pick_weighted(&mut self, weights: &[usize]) -> Option<usize> {
+        let total = weights
+            .iter()
+            .try_fold(0usize, |total, weight| total.checked_add(*weight))?;
+        if total == 0 {
+            return None;
+        }
+        let mut roll = self.rng.gen_range(1, total);
+        weights.iter().position(|weight| {
+            if roll <= *weight {
+                true
+            } else {
+                roll -= weight;
+                false
+            }
+        })
+    }
+
+    /// Rolls the attack of a hit: A crit if the dice rolled the highest result (`n` for a single die),
+    /// a fumble if it rolled the lowest (`1` for a single die), otherwise a normal hit.
+    /// With more dice sides (harder difficulties) crits and fumbles are rarer.
+    pub fn roll_attack(&mut self) -> AttackRoll {
+        let roll = self.roll();
+        if roll == self.sides() {
+            AttackRoll::Crit
+        } else if self.is_fumble(roll) {
+            AttackRoll::Fumble
+        } else {
+            AttackRoll::Hit
+        }
+    }
+
+    /// Returns true if the attack is a critical hit, which is the case if the dice rolled the highest result (see `roll_attack`).
+    /// With more dice sides (harder difficulties) crits are rarer.
+    pub fn roll_crit(&mut self) -> bool {
+        self.roll_attack() == AttackRoll::Crit
+    }
+
+    /// Returns true if the attack is a fumble, which is the case if the dice rolled the lowest result (see `roll_attack`).
+    /// A roll is never both a crit and a fumble.
+    pub fn roll_fumble(&mut self) -> bool {
+        self.roll_attack() == AttackRoll::Fumble
+    }
+
+    /// Returns true if an attack `roll` is a fumble, which is the lowest result (`1` for a single die).
+    fn is_fumble(&self, roll: usize) -> bool {
+        roll == self.lowest()
+    }
+
+    /// Returns true if a defender counterattacks, which is the case if the dice rolled `n`.
+    pub fn roll_counter(&mut self) -> bool {
+        self.throw_dice()
+    }
+
+    /// Returns true if a hit stuns the defender, which is the case if the dice rolled `n`.
+    pub fn roll_stun(&mut self) -> bool {
+        self.throw_dice()
+    }
+
+    /// Returns true if a hit breaks the guard of a defender, which is the case if the dice rolled `n`.
+    pub fn roll_break_guard(&mut self) -> bool {
+        self.throw_dice()
+    }
+
+    /// Returns true if a nimble attacker attacks again, which is the case if the dice rolled `n`.
+    pub fn roll_extra_attack(&mut self) -> bool {
+        self.throw_dice()
+    }
+
+    /// Returns true if an attack is dodged by a defender with `dexterity`.
+    ///
+    /// This is the case if the dice roll applied to the `dexterity` (see `apply_dice_roll`)
+    /// exceeds `DODGE_THRESHOLD`. So only entities with more dexterity than that can dodge at all,
+    /// a higher dexterity makes dodging more likely.
+    pub fn roll_dodge(&mut self, dexterity: usize) -> bool {
+        self.apply_dice_roll(dexterity) > DODGE_THRESHOLD
+    }
+}
+
+/// The result of the attack roll of a hit, see `Dice::roll_attack`.
+/// The highest result is a crit and the lowest a fumble, so an attack can't be both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackRoll {
+    /// Deals no damage, see `Combatant::fumble`.
+    Fumble,
+    Hit,
+    /// Deals `CRIT_MULTIPLIER` times the damage.
+    Crit,
+}
+
+/// The range an attack damage is rolled in: `DAMAGE_SPREAD_PERCENT` below and above the base damage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl DamageRange {
+    pub fn new(base: usize) -> Self {
+        let spread = base.saturating_mul(DAMAGE_SPREAD_PERCENT) / 100;
+        Self {
+            min: base - spread,
+            max: base.saturating_add(spread),
+        }
+    }
+}
+
+impl fmt::Display for DamageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}–{}", self.min, self.max)
+        }
+    }
+}
+
+/// Applies the rolled side `roll` of a dice with `n` sides to `num`: `(roll / n) * num`, rounded down.
+fn apply_roll(roll: usize, n: usize, num: usize) -> usize {
+    ((roll as f64 / n as f64) * num as f64).floor() as usize
+}
+
+/// Difficulty used for setting up Game Rules and Dice sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Dice changes to 1/3
+    Easy,
+    /// Dice changes to 1/6
+    Normal,
+    /// Dice changes to 1/9
+    Hard,
+    /// Dice changes to 1/n, with at least `MIN_DICE_SIDES` sides
+    Custom(usize),
+    /// Dice starts at 1/6 and adapts between the fights, see `GameRules::adapt_difficulty`
+    Dynamic,
+}
+
+/// Parses `easy`, `normal`, `hard` or `dynamic` (ignoring the case) or a count of dice sides for a `Custom` difficulty.
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "easy" => Ok(Self::Easy),
+            "normal" => Ok(Self::Normal),
+            "hard" => Ok(Self::Hard),
+            "dynamic" => Ok(Self::Dynamic),
+            n => n.parse().map(Self::custom).map_err(|_| {
+                format!("Unknown difficulty `{s}`, expected `easy`, `normal`, `hard`, `dynamic` or a count of dice sides")
+            }),
+        }
+    }
+}
+
+impl Difficulty {
+    /// Returns `Difficulty` from `i`. i has to be 0 <= i <= 4 otherwise this function panics!
+    ///
+    /// For `Custom` (i = 3) the user is prompted for the count of dice sides.
+    pub fn from_i(i: usize) -> Self {
+        match i {
+            0 => Self::Easy,
+            1 => Self::Normal,
+            2 => Self::Hard,
+            3 => loop {
+                if let Empty::Some(n) = input::<Empty<usize>>(text(Msg::DiceSidesPrompt)) {
+                    break Self::custom(n);
+                }
+            },
+            4 => Self::Dynamic,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Creates a custom difficulty with `n` dice sides, clamped to at least `MIN_DICE_SIDES`.
+    pub fn custom(n: usize) -> Self {
+        Self::Custom(n.max(MIN_DICE_SIDES))
+    }
+
+    /// Converts the current difficulty to the count of dice sides.
+    pub fn to_dice_n(&self) -> usize {
+        match self {
+            Self::Easy => 3,
+            Self::Normal | Self::Dynamic => 6,
+            Self::Hard => 9,
+            Self::Custom(n) => (*n).max(MIN_DICE_SIDES),
+        }
+    }
+
+    /// Calculates the chance (0 to 1) that an entity with `dexterity` attacks first
+    /// against an enemy with `enemy_dexterity`, ignoring a first strike (see `Combatant::fight`).
+    pub fn first_attack_chance(&self, dexterity: usize, enemy_dexterity: usize) -> f64 {
+        let n = self.to_dice_n();
+        let sides = (1..=n)
+            .filter(|roll| apply_roll(*roll, n, dexterity) > enemy_dexterity)
+            .count();
+        sides as f64 / n as f64
+    }
+
+    /// Calculates the chance (0 to 1) of fleeing with a single die, ignoring the weight of the weapons
+    /// (see `Dice::flee_probability`).
+    pub fn flee_chance(&self) -> f64 {
+        1.0 / self.to_dice_n() as f64
+    }
+
+    /// Percentage applied to the `life_points` and `strength` of the monsters (see `Entity::scale_stats`):
+    /// Easy 75%, Normal 100%, Hard 125% and Custom 100%.
+    pub fn stat_modifier(&self) -> usize {
+        match self {
+            Self::Easy => 75,
+            Self::Normal | Self::Custom(_) | Self::Dynamic => 100,
+            Self::Hard => 125,
+        }
+    }
+}
+
+/// A mage (player) with the option to heal themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mage {
+    pub entity: Entity,
+    magic_power: usize,
+    /// Current mana, needed for healing.
+    #[serde(default)]
+    mana: usize,
+    /// Mana the regeneration stops at.
+    #[serde(default = "default_max_mana")]
+    max_mana: usize,
+    /// Mana consumed per heal.
+    #[serde(default = "default_heal_cost")]
+    heal_cost: usize,
+    /// Life points healed in addition to the staff bonus, see `get_heal_lp`.
+    #[serde(default = "default_base_heal")]
+    base_heal: usize,
+}
+
+fn default_max_mana() -> usize {
+    DEFAULT_MAX_MANA
+}
+
+fn default_heal_cost() -> usize {
+    DEFAULT_HEAL_COST
+}
+
+fn default_base_heal() -> usize {
+    DEFAULT_BASE_HEAL
+}
+
+impl Default for Mage {
+    fn default() -> Self {
+        Self::new(Entity::default(), 0, 0, DEFAULT_HEAL_COST)
+    }
+}
+
+impl Combatant for Mage {
+    fn entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn entity_mut(&mut self) -> &mut Entity {
+        &mut self.entity
+    }
+
+    /// Overwriting the default implementation for `start_round` by regenerating mana, up to the `max_mana`.
+    fn start_round(&mut self) {
+        self.mana = self.mana.saturating_add(MANA_REGEN).min(self.max_mana);
+    }
+
+    /// Overwriting the default implementation for `can_heal`, a mage can always try to heal.
+    fn can_heal(&self) -> bool {
+        true
+    }
+
+    /// Overwriting the default implementation for `heal_option` by showing the heal and the mana.
+    fn heal_option(&self) -> String {
+        msg(
+            Msg::HealOption,
+            &[&self.get_heal_lp(), &self.heal_cost, &self.mana],
+        )
+    }
+
+    /// Overwriting the default implementation for `proficiency`, a mage can't use heavy materials fully.
+    fn proficiency(&self) -> Proficiency {
+        Proficiency::MAGE
+    }
+
+    /// Overwriting the default implementation for `damage_type`, attacks with a spell power are magic.
+    fn damage_type(&self) -> DamageType {
+        if self
+            .entity
+            .weapon
+            .as_ref()
+            .is_some_and(|w| w.spell_power > 0)
+        {
+            DamageType::Magic
+        } else {
+            DamageType::Physical
+        }
+    }
+
+    /// Applys the heal of the mage to it's own health, capped at `max_life_points`.
+    /// The overheal above the cap is added to the shield (see `Entity::shield`).
+    /// Consumes `heal_cost` mana, returns false without healing if there isn't enough mana.
+    fn heal(&mut self, game_rules: &mut GameRules) -> bool {
+        if self.mana < self.heal_cost {
+            reveal(text(Msg::NotEnoughMana), delay());
+            return false;
+        }
+        self.mana -= self.heal_cost;
+        let heal = self.get_heal_lp();
+        let heal_lp = self.entity.heal(heal);
+        reveal(&msg(Msg::Healed, &[&self.name(), &heal_lp]), delay());
+        game_rules.log(&self.entity.name, LogAction::Heal, heal_lp);
+        // The overheal isn't wasted but shields the mage
+        let overheal = heal - heal_lp;
+        if overheal > 0 {
+            self.entity.shield = self.entity.shield.saturating_add(overheal);
+            reveal(&msg(Msg::Shielded, &[&self.name(), &overheal]), delay());
+        }
+        true
+    }
+}
+
+impl Mage {
+    pub fn new(entity: Entity, magic_power: usize, mana: usize, heal_cost: usize) -> Self {
+        Self {
+            entity,
+            magic_power,
+            mana,
+            max_mana: DEFAULT_MAX_MANA,
+            heal_cost,
+            base_heal: DEFAULT_BASE_HEAL,
+        }
+    }
+
+    /// Gets the stats of the class affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 5] {
+        [
+            ("magic_power", self.magic_power),
+            ("mana", self.mana),
+            ("max_mana", self.max_mana),
+            ("heal_cost", self.heal_cost),
+            ("base_heal", self.base_heal),
+        ]
+    }
+
+    /// Calculates the heal lp and returns it: The `base_heal` plus the staff bonus
+    /// of `magic_power * spell_power` (as far as proficient), so an unarmed mage heals the `base_heal`.
+    pub fn get_heal_lp(&self) -> usize {
+        let weapon_power = if let Some(weapon) = &self.entity.weapon {
+            weapon
+                .spell_power
+                .saturating_mul(self.proficiency().spell_power_percent)
+                / 100
+        } else {
+            0
+        };
+        self.base_heal
+            .saturating_add(self.magic_power.saturating_mul(weapon_power))
+    }
+}
+
+impl fmt::Display for Mage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sheet = msg(
+            Msg::SheetMage,
+            &[&self.entity, &self.magic_power, &self.mana, &self.max_mana],
+        );
+        write!(f, "{sheet}")
+    }
+}
+
+/// A fighter (player) with extra endurance which strengthens their attack damage.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Fighter {
+    pub entity: Entity,
+    endurance: usize,
+}
+
+impl Combatant for Fighter {
+    fn entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn entity_mut(&mut self) -> &mut Entity {
+        &mut self.entity
+    }
+
+    /// Overwriting the default implementation for `attack_damage` by adding an endurance multiplier.
+    fn attack_damage(&self) -> usize {
+        let entity = self.entity();
+        let norm_attack = entity
+            .proficient_weapon_damage(self.proficiency())
+            .saturating_add(entity.strength);
+        norm_attack.saturating_mul(self.endurance)
+    }
+
+    /// Overwriting the default implementation for `weapon_attack_damage` with the endurance multiplier.
+    fn weapon_attack_damage(&self) -> usize {
+        self.entity
+            .proficient_weapon_damage(self.proficiency())
+            .saturating_mul(self.endurance)
+    }
+
+    /// Overwriting the default implementation for `proficiency`, a fighter can't use spell power.
+    fn proficiency(&self) -> Proficiency {
+        Proficiency::FIGHTER
+    }
+
+    /// Overwriting the default implementation for `can_stun`, a fighter with `STUN_ENDURANCE` hits stunning.
+    fn can_stun(&self) -> bool {
+        self.endurance >= STUN_ENDURANCE
+    }
+
+    /// Overwriting the default implementation for `can_break_guard`, a fighter with `GUARD_BREAK_ENDURANCE` hits heavily.
+    fn can_break_guard(&self) -> bool {
+        self.endurance >= GUARD_BREAK_ENDURANCE
+    }
+
+    /// Overwriting the default implementation for `can_taunt`, a fighter draws the attacks in party fights.
+    fn can_taunt(&self) -> bool {
+        true
+    }
+
+    /// Overwriting the default implementation for `on_hit`, healing `LIFESTEAL_PERCENT`
+    /// per endurance of the applied damage, at most `MAX_LIFESTEAL_PERCENT` (capped at the max life points).
+    fn on_hit(&mut self, dmg: usize) {
+        let percent = self
+            .endurance
+            .saturating_mul(LIFESTEAL_PERCENT)
+            .min(MAX_LIFESTEAL_PERCENT);
+        let steal = dmg.saturating_mul(percent) / 100;
+        let heal_lp = self.entity.heal(steal);
+        if heal_lp > 0 {
+            reveal(&msg(Msg::LifeDrained, &[&self.name(), &heal_lp]), delay());
+        }
+    }
+}
+
+impl Fighter {
+    pub fn new(entity: Entity, endurance: usize) -> Self {
+        Self { entity, endurance }
+    }
+
+    /// Gets the stats of the class affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 1] {
+        [("endurance", self.endurance)]
+    }
+}
+
+impl fmt::Display for Fighter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            msg(Msg::SheetFighter, &[&self.entity, &self.endurance])
+        )
+    }
+}
+
+/// An archer (player) with extra accuracy which strengthens their attack damage.
+/// Always attacks first in the opening round.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Archer {
+    pub entity: Entity,
+    accuracy: usize,
+}
+
+impl Combatant for Archer {
+    fn entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn entity_mut(&mut self) -> &mut Entity {
+        &mut self.entity
+    }
+
+    /// Overwriting the default implementation for `attack_damage` by adding the accuracy.
+    fn attack_damage(&self) -> usize {
+        let entity = self.entity();
+        let norm_attack = entity.weapon_damage().saturating_add(entity.strength);
+        norm_attack.saturating_add(self.accuracy)
+    }
+
+    /// Overwriting the default implementation for `first_strike`, a ranged opener.
+    fn first_strike(&self) -> bool {
+        true
+    }
+}
+
+impl Archer {
+    pub fn new(entity: Entity, accuracy: usize) -> Self {
+        Self { entity, accuracy }
+    }
+
+    /// Gets the stats of the class affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 1] {
+        [("accuracy", self.accuracy)]
+    }
+}
+
+impl fmt::Display for Archer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            msg(Msg::SheetArcher, &[&self.entity, &self.accuracy])
+        )
+    }
+}
+
+/// A rogue (player) whose first hit in a fight is a backstab, dealing extra damage based on stealth.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Rogue {
+    pub entity: Entity,
+    stealth: usize,
+    /// Whether the backstab was already used in the current fight.
+    #[serde(skip)]
+    backstabbed: bool,
+}
+
+impl Combatant for Rogue {
+    fn entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn entity_mut(&mut self) -> &mut Entity {
+        &mut self.entity
+    }
+
+    /// Overwriting the default implementation for `attack_damage` by adding the stealth
+    /// until the backstab was used.
+    fn attack_damage(&self) -> usize {
+        let entity = self.entity();
+        let norm_attack = entity.weapon_damage().saturating_add(entity.strength);
+        if self.backstabbed {
+            norm_attack
+        } else {
+            norm_attack.saturating_add(self.stealth)
+        }
+    }
+
+    /// Overwriting the default implementation for `on_hit` by using up the backstab.
+    fn on_hit(&mut self, _dmg: usize) {
+        if !self.backstabbed {
+            self.backstabbed = true;
+            reveal(&msg(Msg::Backstab, &[&self.name(), &self.stealth]), delay());
+        }
+    }
+
+    /// Overwriting the default implementation for `start_fight` by readying the backstab.
+    fn start_fight(&mut self) {
+        self.backstabbed = false;
+    }
+}
+
+impl Rogue {
+    pub fn new(entity: Entity, stealth: usize) -> Self {
+        Self {
+            entity,
+            stealth,
+            backstabbed: false,
+        }
+    }
+
+    /// Gets the stats of the class affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 1] {
+        [("stealth", self.stealth)]
+    }
+}
+
+impl fmt::Display for Rogue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            msg(Msg::SheetRogue, &[&self.entity, &self.stealth])
+        )
+    }
+}
+
+/// How a monster chooses its actions, see `Monster::with_ai`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AiBehavior {
+    /// Always attacks.
+    #[default]
+    Aggressive,
+    /// Defends if the dice rolled `n`, otherwise attacks.
+    Defensive,
+    /// Tries to flee below `MONSTER_FLEE_THRESHOLD`, otherwise attacks.
+    Fleeing,
+}
+
+/// A monster struct which the player fights against.
+///
+/// With `phases` the monster becomes a boss, which gets stronger with every crossed phase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Monster {
+    pub entity: Entity,
+    /// How the monster chooses its actions.
+    #[serde(default)]
+    ai: AiBehavior,
+    /// Percent of the stats gained per level of the player above the first, see `scale_to_level`.
+    #[serde(default = "default_level_scaling")]
+    level_scaling: usize,
+    /// Life point thresholds in percent of the max life points, e.g. `[50, 25]`.
+    /// Falling below a threshold starts the next phase.
+    #[serde(default)]
+    phases: Vec<usize>,
+    /// The current phase, starting at 1.
+    #[serde(skip)]
+    phase: usize,
+    /// Count of crossed `ENRAGE_THRESHOLDS` at the last reveal, see `reveal_enrage`.
+    #[serde(skip)]
+    revealed_enrage: usize,
+    /// Possible drops when defeated, see `roll_loot`.
+    #[serde(default)]
+    loot: Vec<LootEntry>,
+}
+
+fn default_level_scaling() -> usize {
+    DEFAULT_LEVEL_SCALING
+}
+
+impl Default for Monster {
+    fn default() -> Self {
+        Self::new(Entity::default(), vec![], vec![])
+    }
+}
+
+impl Combatant for Monster {
+    fn entity(&self) -> &Entity {
+        &self.entity
+    }
+
+    fn entity_mut(&mut self) -> &mut Entity {
+        &mut self.entity
+    }
+
+    /// Overwriting the default implementation for `attack_damage`,
+    /// adding `ENRAGE_STRENGTH_PERCENT` of the strength for every crossed `ENRAGE_THRESHOLDS`
+    /// and `PHASE_STRENGTH_BONUS` for every phase after the first.
+    fn attack_damage(&self) -> usize {
+        let entity = self.entity();
+        let strength = entity.strength.saturating_add(
+            entity
+                .strength
+                .saturating_mul(self.enrage() * ENRAGE_STRENGTH_PERCENT)
+                / 100,
+        );
+        let norm_attack = entity.weapon_damage().saturating_add(strength);
+        norm_attack.saturating_add(self.phase.saturating_sub(1) * PHASE_STRENGTH_BONUS)
+    }
+
+    /// Overwriting the default implementation for `start_fight` to start in the first phase, calm.
+    fn start_fight(&mut self) {
+        self.phase = 1;
+        self.revealed_enrage = 0;
+    }
+
+    /// Overwriting the default implementation for `heal`, healing `MONSTER_HEAL_PERCENT` of the max life points.
+    /// Only called on `Hard`, see `select_action`.
+    fn heal(&mut self, game_rules: &mut GameRules) -> bool {
+        let heal = self
+            .entity
+            .max_life_points
+            .saturating_mul(MONSTER_HEAL_PERCENT)
+            / 100;
+        let heal_lp = self.entity.heal(heal);
+        self.entity.combo = 0;
+        reveal(&msg(Msg::Healed, &[&self.name(), &heal_lp]), delay());
+        game_rules.log(&self.entity.name, LogAction::Heal, heal_lp);
+        true
+    }
+
+    /// Overwriting the default implementation for `select_action` by removing all options.
+    /// A monster will always attack, after advancing its phase and revealing its enrage.
+    /// On `Hard` a monster below `MONSTER_HEAL_THRESHOLD` heals itself instead if the dice rolled `n`.
+    fn select_action<E: Combatant>(&mut self, enemy: &mut E, game_rules: &mut GameRules) -> bool {
+        self.advance_phase();
+        self.reveal_enrage();
+        if game_rules.difficulty == Difficulty::Hard
+            && self.wants_to_heal()
+            && game_rules.dice.throw_dice()
+        {
+            self.heal(game_rules);
+            return false;
+        }
+        match self.ai {
+            AiBehavior::Defensive if game_rules.dice.throw_dice() => {
+                self.defend(game_rules);
+                false
+            }
+            AiBehavior::Fleeing if self.wants_to_flee() => self.flee(game_rules),
+            _ => self.attack_turn(enemy, game_rules),
+        }
+    }
+}
+
+impl Monster {
+    pub fn new(entity: Entity, phases: Vec<usize>, loot: Vec<LootEntry>) -> Self {
+        Self {
+            entity,
+            ai: AiBehavior::default(),
+            level_scaling: DEFAULT_LEVEL_SCALING,
+            phases,
+            phase: 1,
+            revealed_enrage: 0,
+            loot,
+        }
+    }
+
+    /// Gets the stats of the monster (besides the entity) affecting the fight math by their config field name,
+    /// e.g. to check their bounds: The `level_scaling`, every threshold of the `phases` and the stats of the `loot`.
+    pub fn numeric_stats(&self) -> Vec<(&'static str, usize)> {
+        let phases = self.phases.iter().map(|&threshold| ("phases", threshold));
+        let loot = self.loot.iter().flat_map(LootEntry::numeric_stats);
+        [("level_scaling", self.level_scaling)]
+            .into_iter()
+            .chain(phases)
+            .chain(loot)
+            .collect()
+    }
+
+    /// Sets how the monster chooses its actions, `Aggressive` by default.
+    pub fn with_ai(mut self, ai: AiBehavior) -> Self {
+        self.ai = ai;
+        self
+    }
+
+    pub fn ai(&self) -> AiBehavior {
+        self.ai
+    }
+
+    /// Sets the percent of the stats gained per level of the player, `DEFAULT_LEVEL_SCALING` by default.
+    pub fn with_level_scaling(mut self, level_scaling: usize) -> Self {
+        self.level_scaling = level_scaling;
+        self
+    }
+
+    /// Scales the stats to a player of `level`, so fights stay challenging after level ups:
+    /// `level_scaling` percent more for every level above the first (see `Entity::scale_stats`).
+    pub fn scale_to_level(&mut self, level: usize) {
+        let bonus = level.saturating_sub(1).saturating_mul(self.level_scaling);
+        if bonus > 0 {
+            self.entity.scale_stats(100usize.saturating_add(bonus));
+        }
+    }
+
+    /// Returns true if a `Fleeing` monster is below `MONSTER_FLEE_THRESHOLD` and may flee again.
+    fn wants_to_flee(&self) -> bool {
+        let entity = &self.entity;
+        entity.flee_cooldown == 0
+            && entity.life_points.saturating_mul(100)
+                < entity
+                    .max_life_points
+                    .saturating_mul(MONSTER_FLEE_THRESHOLD)
+    }
+
+    /// Returns true if the monster is below `MONSTER_HEAL_THRESHOLD`, see `select_action`.
+    fn wants_to_heal(&self) -> bool {
+        self.entity.life_points.saturating_mul(100)
+            < self
+                .entity
+                .max_life_points
+                .saturating_mul(MONSTER_HEAL_THRESHOLD)
+    }
+
+    /// Gets the count of `ENRAGE_THRESHOLDS` the current life points are below,
+    /// so a monster healing above a threshold calms down again.
+    fn enrage(&self) -> usize {
+        let (life_points, max_life_points) = (self.entity.life_points, self.entity.max_life_points);
+        ENRAGE_THRESHOLDS
+            .iter()
+            .filter(|&&threshold| {
+                life_points.saturating_mul(100) < max_life_points.saturating_mul(threshold)
+            })
+            .count()
+    }
+
+    /// Reveals that the monster got enraged if it crossed another threshold since the last reveal.
+    fn reveal_enrage(&mut self) {
+        let enrage = self.enrage();
+        if enrage > self.revealed_enrage {
+            reveal(&msg(Msg::Enraged, &[&self.name()]), delay());
+        }
+        self.revealed_enrage = enrage;
+    }
+
+    /// Rolls which entry of the `loot` drops, weighted by `LootEntry::weight`, and removes it.
+    /// Returns `None` if there is no loot (left).
+    pub fn roll_loot(&mut self, game_rules: &mut GameRules) -> Option<Loot> {
+        let weights: Vec<usize> = self.loot.iter().map(|entry| entry.weight).collect();
+        let i = game_rules.dice.pick_weighted(&weights)?;
+        Some(self.loot.swap_remove(i).drop)
+    }
+
+    /// Advances to the phase of the current life points, one for every crossed threshold of `phases`.
+    fn advance_phase(&mut self) {
+        let (life_points, max_life_points) = (self.entity.life_points, self.entity.max_life_points);
+        let phase = 1 + self
+            .phases
+            .iter()
+            .filter(|&&threshold| {
+                life_points.saturating_mul(100) < max_life_points.saturating_mul(threshold)
+            })
+            .count();
+        if phase > self.phase {
+            self.phase = phase;
+            reveal(&msg(Msg::PhaseChange, &[&self.name(), &phase]), delay());
+        }
+    }
+}
+
+impl fmt::Display for Monster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ai = format!("{:?}", self.ai);
+        write!(f, "{}", msg(Msg::SheetMonster, &[&self.entity, &ai]))
+    }
+}
+
+/// A possible drop of a monster with its `weight` relative to the other entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootEntry {
+    pub weight: usize,
+    pub drop: Loot,
+}
+
+impl LootEntry {
+    /// Gets the `weight` and the stats of the drop by their config field name, e.g. to check their bounds:
+    /// The boost of a stat or the stats of a weapon (see `Weapon::numeric_stats`).
+    pub fn numeric_stats(&self) -> Vec<(&'static str, usize)> {
+        let drop = match &self.drop {
+            Loot::Nothing => vec![],
+            Loot::Weapon(weapon) => weapon.numeric_stats().to_vec(),
+            Loot::Strength(boost) => vec![("Strength", *boost)],
+            Loot::Dexterity(boost) => vec![("Dexterity", *boost)],
+        };
+        [("weight", self.weight)].into_iter().chain(drop).collect()
+    }
+}
+
+/// What a monster drops.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Loot {
+    /// Nothing drops, for a chance of no loot.
+    Nothing,
+    /// A weapon, which is equipped if it deals more damage than the current one.
+    Weapon(Weapon),
+    /// A boost of the strength.
+    Strength(usize),
+    /// A boost of the dexterity.
+    Dexterity(usize),
+}
+
+/// Weapon can have different material, a spell power (if seen as a staff),
+/// a poison which is applied on every hit, a durability, can be two-handed and can be enchanted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Weapon {
+    material: Material,
+    pub spell_power: usize,
+    /// Poison damage per round, `0` for no poison.
+    #[serde(default)]
+    pub poison: usize,
+    /// Remaining attacks until the weapon breaks, `None` for unbreakable.
+    #[serde(default)]
+    pub durability: Option<usize>,
+    /// Two-handed weapons hit harder but lower the dexterity of the wielder.
+    #[serde(default)]
+    pub two_handed: bool,
+    /// Effect applied on every hit, `None` for no enchantment.
+    #[serde(default)]
+    pub enchantment: Option<Enchantment>,
+}
+
+impl Weapon {
+    pub fn new(
+        material: Material,
+        spell_power: usize,
+        poison: usize,
+        durability: Option<usize>,
+        two_handed: bool,
+        enchantment: Option<Enchantment>,
+    ) -> Self {
+        Self {
+            material,
+            spell_power,
+            poison,
+            durability,
+            two_handed,
+            enchantment,
+        }
+    }
+
+    /// Gets the stats affecting the fight math by their config field name, e.g. to check their bounds.
+    pub fn numeric_stats(&self) -> [(&'static str, usize); 3] {
+        [
+            ("modifier", self.material.calc_modifier()),
+            ("spell_power", self.spell_power),
+            ("poison", self.poison),
+        ]
+    }
+
+    /// Gets the level needed to wield the weapon, the one of its material (see `Material::min_level`).
+    pub fn min_level(&self) -> usize {
+        self.material.min_level()
+    }
+
+    /// Gets the weight of the material, doubled if two-handed.
+    pub fn weight(&self) -> usize {
+        let weight = self.material.weight();
+        if self.two_handed {
+            weight.saturating_mul(2)
+        } else {
+            weight
+        }
+    }
+
+    /// Calculate damage modifier of the weapon, adding `TWO_HANDED_DAMAGE_BONUS` if two-handed.
+    /// Saturates at `usize::MAX` instead of overflowing.
+    pub fn calc_damage(&self) -> usize {
+        self.proficient_damage(Proficiency::FULL)
+    }
+
+    /// Same as `calc_damage` but with the material (if heavy) and the spell power reduced by the `proficiency`.
+    pub fn proficient_damage(&self, proficiency: Proficiency) -> usize {
+        let bonus = if self.two_handed {
+            TWO_HANDED_DAMAGE_BONUS
+        } else {
+            0
+        };
+        let modifier = self.material.calc_modifier();
+        let modifier = if self.material.is_heavy() {
+            modifier.saturating_mul(proficiency.heavy_material_percent) / 100
+        } else {
+            modifier
+        };
+        let spell_power = self
+            .spell_power
+            .saturating_mul(proficiency.spell_power_percent)
+            / 100;
+        modifier.saturating_add(spell_power).saturating_add(bonus)
+    }
+
+    /// Starts building a weapon with fluent setters, see `WeaponBuilder`.
+    pub fn builder() -> WeaponBuilder {
+        WeaponBuilder::default()
+    }
+}
+
+/// Builds a `Weapon` field by field. Unset fields are like in the config:
+/// A `Wood` weapon without spell power, poison or enchantment, which is one-handed and unbreakable.
+#[derive(Debug, Clone)]
+pub struct WeaponBuilder {
+    weapon: Weapon,
+}
+
+impl Default for WeaponBuilder {
+    fn default() -> Self {
+        Self {
+            weapon: Weapon::new(Material::Wood, 0, 0, None, false, None),
+        }
+    }
+}
+
+impl WeaponBuilder {
+    pub fn material(mut self, material: Material) -> Self {
+        self.weapon.material = material;
+        self
+    }
+
+    pub fn spell_power(mut self, spell_power: usize) -> Self {
+        self.weapon.spell_power = spell_power;
+        self
+    }
+
+    pub fn poison(mut self, poison: usize) -> Self {
+        self.weapon.poison = poison;
+        self
+    }
+
+    /// Sets the remaining attacks until the weapon breaks.
+    pub fn durability(mut self, durability: usize) -> Self {
+        self.weapon.durability = Some(durability);
+        self
+    }
+
+    pub fn two_handed(mut self) -> Self {
+        self.weapon.two_handed = true;
+        self
+    }
+
+    pub fn enchantment(mut self, enchantment: Enchantment) -> Self {
+        self.weapon.enchantment = Some(enchantment);
+        self
+    }
+
+    pub fn build(self) -> Weapon {
+        self.weapon
+    }
+}
+
+/// How much of the parts of a weapon a class can use, in percent (see `Combatant::proficiency`):
+///
+/// | Class     | Heavy materials (`Material::is_heavy`) | Spell power |
+/// |-----------|----------------------------------------|-------------|
+/// | `Fighter` | 100%                                   | 0%          |
+/// | `Mage`    | 50%                                    | 100%        |
+/// | `Archer`  | 100%                                   | 100%        |
+/// | `Rogue`   | 100%                                   | 100%        |
+/// | `Monster` | 100%                                   | 100%        |
+///
+/// Other materials and the two-handed bonus are always fully usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proficiency {
+    pub heavy_material_percent: usize,
+    pub spell_power_percent: usize,
+}
+
+impl Proficiency {
+    /// Uses every part of a weapon fully.
+    pub const FULL: Self = Self {
+        heavy_material_percent: 100,
+        spell_power_percent: 100,
+    };
+    pub const FIGHTER: Self = Self {
+        heavy_material_percent: 100,
+        spell_power_percent: 0,
+    };
+    pub const MAGE: Self = Self {
+        heavy_material_percent: 50,
+        spell_power_percent: 100,
+    };
+}
+
+/// The type of the damage of an attack, see `Combatant::damage_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageType {
+    /// Attacks by strength and weapons, e.g. of a `Fighter`.
+    Physical,
+    /// Attacks by a weapon with a spell power of a `Mage`.
+    Magic,
+}
+
+/// Enchantment of a weapon, applied after the damage of a hit resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Enchantment {
+    /// Burns the target for `FIRE_BURN_DMG` damage, halved every round (see `Entity::burn`).
+    Fire,
+    /// Lowers the dexterity of the target by `FROST_DEXTERITY_PENALTY` for its next dodge.
+    Frost,
+    /// Heals the attacker by the dealt damage divided by `VAMPIRIC_HEAL_DIVISOR`.
+    Vampiric,
+}
+
+// Material of the weapon. `Wood` is the weakest and `Diamond` the strongest predefined material.
+// A `Custom` material has any name and modifier, e.g. `{ "Custom": { "name": "Mithril", "modifier": 8 } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Material {
+    Wood,
+    Stone,
+    Iron,
+    Gold,
+    MagicOre,
+    Diamond,
+    Custom { name: String, modifier: usize },
+}
+
+impl Material {
+    // Calculating the material modifier. Used for damage calculation.
+    pub fn calc_modifier(&self) -> usize {
+        match self {
+            Self::Wood => 1,
+            Self::Stone => 2,
+            Self::Iron => 3,
+            Self::Gold => 4,
+            Self::MagicOre => 5,
+            Self::Diamond => 6,
+            Self::Custom { modifier, .. } => *modifier,
+        }
+    }
+
+    /// Gets the weight of the material, which makes fleeing harder (see `Entity::flee_penalty`):
+    /// A heavy material (see `is_heavy`) weighs as much as its modifier, any other half of it (rounded up).
+    /// So `Wood` and `Stone` weigh 1, `Gold` 2, `Iron` and `MagicOre` 3 and `Diamond` 6.
+    pub fn weight(&self) -> usize {
+        let modifier = self.calc_modifier();
+        if self.is_heavy() {
+            modifier
+        } else {
+            modifier.div_ceil(2)
+        }
+    }
+
+    /// Gets the level needed to wield the material (see `Entity::can_wield`), so stronger materials are unlocked by levelling up.
+    /// Custom materials need half their modifier (rounded up).
+    pub fn min_level(&self) -> usize {
+        match self {
+            Self::Wood => 1,
+            Self::Stone => 1,
+            Self::Iron => 2,
+            Self::Gold => 3,
+            Self::MagicOre => 4,
+            Self::Diamond => 5,
+            Self::Custom { modifier, .. } => modifier.div_ceil(2).max(1),
+        }
+    }
+
+    /// Returns true for the heavy materials `Iron` and `Diamond`, which a `Mage` can't use fully (see `Proficiency`).
+    pub fn is_heavy(&self) -> bool {
+        matches!(self, Self::Iron | Self::Diamond)
+    }
+
+    /// Iterates over the predefined materials, from the weakest to the strongest.
+    pub fn all() -> impl Iterator<Item = Material> {
+        [
+            Self::Wood,
+            Self::Stone,
+            Self::Iron,
+            Self::Gold,
+            Self::MagicOre,
+            Self::Diamond,
+        ]
+        .into_iter()
+    }
+
+    /// Gets the strongest predefined material.
+    pub fn strongest() -> Self {
+        Self::all().max().unwrap_or(Self::Diamond)
+    }
+}
+
+/// Orders the materials by `calc_modifier`, a stronger material is greater.
+/// Ties (only possible with custom materials) are ordered by the name, predefined materials first.
+impl Ord for Material {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        let key = |material: &Self| {
+            let name = match material {
+                Self::Custom { name, .. } => Some(name.clone()),
+                _ => None,
+            };
+            (material.calc_modifier(), name)
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Material {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Custom { name, .. } => write!(f, "{name}"),
+            material => write!(f, "{material:?}"),
+        }
+    }
+}
+
+/// Fight order: Who attacks first in a round.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Ordering {
+    Player,
+    Enemy,
+}
+
+/// Determines the fight order; Enemy has constant dexterity; the initiator of the fight, the `player`, has to roll.
+/// A tie is broken by the `TieBreak` of the game rules.
+fn roll_initiative(game_rules: &mut GameRules, player: &Entity, enemy: &Entity) -> Ordering {
+    let enemy_dexterity = enemy.effective_dexterity();
+    let mut rerolls = 0;
+    loop {
+        let rolled = game_rules
+            .dice
+            .apply_dice_roll(player.effective_dexterity());
+        match rolled.cmp(&enemy_dexterity) {
+            cmp::Ordering::Greater => return Ordering::Player,
+            cmp::Ordering::Less => return Ordering::Enemy,
+            cmp::Ordering::Equal => match game_rules.tie_break {
+                TieBreak::Initiator => return Ordering::Player,
+                TieBreak::Reroll if rerolls < MAX_INITIATIVE_REROLLS => rerolls += 1,
+                TieBreak::Defender | TieBreak::Reroll => return Ordering::Enemy,
+            },
+        }
+    }
+}
+
+impl Debug for Ordering {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Player => write!(f, "Spieler"),
-            Self::Enemy => write!(f, "Gegner"),
+            Self::Player => write!(f, "{}", text(Msg::Player)),
+            Self::Enemy => write!(f, "{}", text(Msg::Enemy)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        action::{AutoPlayer, ScriptedActions},
+        random::FixedSequence,
+    };
+
+    fn entity(name: &str, life_points: usize, dexterity: usize, strength: usize) -> Entity {
+        Entity::new(name.into(), life_points, dexterity, strength, None, 0)
+    }
+
+    /// Game rules on `Normal` rolling the preset `rolls`.
+    fn rules(rolls: impl IntoIterator<Item = usize>) -> GameRules {
+        GameRules::with_random(Difficulty::Normal, FixedSequence::new(rolls))
+    }
+
+    #[test]
+    fn same_seed_rolls_the_same() {
+        let mut a = Dice::with_seed(6, 42);
+        let mut b = Dice::with_seed(6, 42);
+        let rolls: Vec<usize> = (0..20).map(|_| a.apply_dice_roll(60)).collect();
+        assert_eq!(
+            rolls,
+            (0..20).map(|_| b.apply_dice_roll(60)).collect::<Vec<_>>()
+        );
+        // Every roll is one of the six sides times 10
+        assert!(rolls
+            .iter()
+            .all(|roll| roll % 10 == 0 && (10..=60).contains(roll)));
+        let throws: Vec<bool> = (0..20).map(|_| a.throw_dice()).collect();
+        assert_eq!(throws, (0..20).map(|_| b.throw_dice()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn verbose_mode_reports_the_rolls_of_the_rng() {
+        use crate::output::{set_sink, BufferSink};
+
+        let buffer = BufferSink::new();
+        let previous = set_sink(Box::new(buffer.clone()));
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 7);
+        assert!(!game_rules.verbose());
+        game_rules.dice.apply_dice_roll(6);
+        assert!(buffer.contents().is_empty());
+
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 7);
+        game_rules.set_verbose(true);
+        let mut rng = SmallRng::seed_from_u64(7);
+        let expected: String = (0..6)
+            .map(|_| msg(Msg::DiceRoll, &[&rng.gen_range(1, 6), &6]))
+            .collect();
+        for _ in 0..3 {
+            game_rules.dice.apply_dice_roll(6);
+            game_rules.dice.throw_dice();
+        }
+        set_sink(previous);
+        assert_eq!(buffer.contents(), expected);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_game_rules_dice() {
+        let mut a = GameRules::with_seed(Difficulty::Normal, 7);
+        let mut b = GameRules::with_seed(Difficulty::Normal, 7);
+        let rolls = |game_rules: &mut GameRules| -> Vec<usize> {
+            (0..20)
+                .map(|_| game_rules.dice.apply_dice_roll(6))
+                .collect()
+        };
+        assert_eq!(rolls(&mut a), rolls(&mut b));
+    }
+
+    #[test]
+    fn armor_reduces_damage_to_at_least_one() {
+        let mut armored = Entity::new("Knight".into(), 20, 0, 0, None, 5);
+        assert_eq!(armored.reduce_dmg(3), 1);
+        assert_eq!(armored.reduce_dmg(5), 1);
+        assert_eq!(armored.reduce_dmg(8), 3);
+        armored.apply_dmg(8);
+        assert_eq!(armored.life_points, 17);
+    }
+
+    #[test]
+    fn crit_doubles_the_damage() {
+        // The dodge, damage, crit and counter roll
+        for (rolls, life_points) in [([3, 4, 6, 1], 50 - 4 * CRIT_MULTIPLIER), ([3, 4, 3, 1], 46)] {
+            let mut game_rules = rules(rolls);
+            let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+            let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+            player.attack(&mut monster, &mut game_rules);
+            assert_eq!(monster.entity.life_points, life_points);
+        }
+    }
+
+    #[test]
+    fn fumble_deals_no_damage_and_hurts_on_hard() {
+        // The dodge, damage and fumble roll, a fumble ends the attack without a counter roll
+        let mut game_rules = rules([3, 4, 1]);
+        let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+        player.entity.combo = 2;
+        assert!(!player.attack(&mut monster, &mut game_rules));
+        assert_eq!(monster.entity.life_points, 50);
+        assert_eq!(player.entity.combo, 0);
+        assert_eq!(player.entity.life_points, 10);
+        assert_eq!(
+            game_rules.log_entries().last().unwrap().action,
+            LogAction::Fumble
+        );
+
+        // On hard the attacker hits itself with half of the damage
+        let mut game_rules =
+            GameRules::with_random(Difficulty::Hard, FixedSequence::new([3, 4, 1]));
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points, 50);
+        assert_eq!(player.entity.life_points, 10 - 4 / FUMBLE_SELF_DMG_DIVISOR);
+    }
+
+    #[test]
+    fn attack_roll_is_either_a_crit_or_a_fumble() {
+        let mut dice = Dice::with_random(6, FixedSequence::new([6, 1, 3]));
+        let rolls: Vec<_> = (0..3).map(|_| dice.roll_attack()).collect();
+        assert_eq!(
+            rolls,
+            [AttackRoll::Crit, AttackRoll::Fumble, AttackRoll::Hit]
+        );
+        // The only side of a single sided die is the highest and lowest at once, but only a crit
+        let mut dice = Dice::with_random(1, FixedSequence::new([1]));
+        assert_eq!(dice.roll_attack(), AttackRoll::Crit);
+        let mut dice = Dice::with_random(6, FixedSequence::new([6, 6, 1, 1]));
+        assert_eq!((dice.roll_crit(), dice.roll_fumble()), (true, false));
+        assert_eq!((dice.roll_crit(), dice.roll_fumble()), (false, true));
+    }
+
+    #[test]
+    fn guard_breaking_hits_ignore_the_defend_reduction() {
+        // The dodge, damage, crit, guard break, stun and counter roll (as far as rolled),
+        // the base damage of a fighter is the strength times the endurance
+        for (rolls, strength, endurance, life_points) in [
+            (vec![3, 4, 3, 1], 4, 1, 50 - 4 / 2),
+            (vec![3, 4, 6, 1], 4, 1, 50 - 4 * CRIT_MULTIPLIER),
+            (vec![3, 8, 3, 5, 1, 1], 1, GUARD_BREAK_ENDURANCE, 50 - 8 / 2),
+            (vec![3, 8, 3, 6, 1, 1], 1, GUARD_BREAK_ENDURANCE, 50 - 8),
+        ] {
+            let mut game_rules = rules(rolls);
+            let mut player = Fighter::new(entity("Hero", 10, 0, strength), endurance);
+            let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+            monster.entity.defending = true;
+            player.attack(&mut monster, &mut game_rules);
+            assert_eq!(monster.entity.life_points, life_points);
+            assert!(!monster.entity.defending);
+        }
+    }
+
+    #[test]
+    fn nimble_attacker_attacks_again_up_to_the_cap() {
+        // Every attack rolls the dodge, damage, crit and counter roll, then the extra attack is rolled
+        // until it fails or the cap is reached, with the combo bonus adding up
+        for (dexterity, rolls, life_points) in [
+            (EXTRA_ATTACK_DEXTERITY, vec![3, 4, 3, 1], 50 - 4),
+            (EXTRA_ATTACK_DEXTERITY + 1, vec![3, 4, 3, 1, 5], 50 - 4),
+            (
+                EXTRA_ATTACK_DEXTERITY + 1,
+                vec![3, 4, 3, 1, 6, 3, 4, 3, 1, 6, 3, 4, 3, 1],
+                50 - 4 - (4 + COMBO_DAMAGE_BONUS) - (4 + 2 * COMBO_DAMAGE_BONUS),
+            ),
+        ] {
+            let mut game_rules = rules(rolls);
+            let mut player = Fighter::new(entity("Hero", 10, dexterity, 4), 1);
+            let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+            assert!(!player.attack_turn(&mut monster, &mut game_rules));
+            assert_eq!(monster.entity.life_points, life_points);
+        }
+    }
+
+    #[test]
+    fn heal_is_capped_at_max_life_points() {
+        let mut hero = entity("Hero", 20, 0, 0);
+        assert_eq!(hero.heal(10), 0);
+        hero.apply_dmg(5);
+        assert_eq!(hero.heal(10), 5);
+        assert_eq!(hero.life_points, hero.max_life_points);
+        hero.max_life_points = 0;
+        hero.init_max_life_points();
+        assert_eq!(hero.heal(usize::MAX), 0);
+    }
+
+    #[test]
+    fn defend_halves_only_the_next_hit() {
+        let mut game_rules = rules([3, 8, 3, 1, 3, 8, 3, 1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 1), 1);
+        let mut monster = Monster::new(entity("Orc", 50, 0, 8), vec![], vec![]);
+        player.defend(&mut game_rules);
+        monster.attack(&mut player, &mut game_rules);
+        assert_eq!(player.entity.life_points, 46);
+        assert!(!player.entity.defending);
+        // The second attack in a row adds the combo bonus
+        monster.attack(&mut player, &mut game_rules);
+        assert_eq!(player.entity.life_points, 36);
+    }
+
+    #[test]
+    fn scripted_actions_drive_a_fight() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 1);
+        // Defend, then attack
+        game_rules.set_actions(ScriptedActions::new([1, 0]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 4, 0, 5), vec![], vec![]);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(result.outcome, FightOutcome::PlayerWon);
+        assert_eq!(result.rounds, 2);
+        assert!(player.entity.life_points < 10);
+        assert!(!player.entity.defending);
+    }
+
+    #[test]
+    fn experience_levels_up_at_every_threshold() {
+        let mut hero = entity("Hero", 20, 0, 0);
+        hero.award_xp(XP_PER_LEVEL - 1);
+        assert_eq!(hero.level, 1);
+        hero.award_xp(1);
+        assert_eq!(hero.level, 2);
+        assert_eq!(hero.strength, LEVEL_UP_STRENGTH);
+        assert_eq!(hero.max_life_points, 20 + LEVEL_UP_LIFE_POINTS);
+        // 350 experience crosses the thresholds of level 2 and 3 at once
+        hero.award_xp(250);
+        assert_eq!(hero.level, 4);
+        assert_eq!(hero.strength, 3 * LEVEL_UP_STRENGTH);
+
+        let json = serde_json::to_string(&hero).unwrap();
+        let loaded: Entity = serde_json::from_str(&json).unwrap();
+        assert_eq!((loaded.level, loaded.experience), (4, 350));
+
+        // Absurd experience saturates instead of overflowing
+        hero.level = usize::MAX / XP_PER_LEVEL;
+        hero.experience = usize::MAX - 1;
+        hero.award_xp(10);
+        assert_eq!(hero.experience, usize::MAX);
+        assert_eq!(hero.level, usize::MAX / XP_PER_LEVEL + 1);
+    }
+
+    #[test]
+    fn archer_strikes_first_despite_low_dexterity() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 1);
+        // A single attack, if the monster went first it would hit the archer
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut archer = Archer::new(entity("Robin", 10, 1, 1), 1);
+        let mut monster = Monster::new(entity("Orc", 1, 10, 1), vec![], vec![]);
+        archer.fight(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points, 0);
+        assert_eq!(archer.entity.life_points, 10);
+    }
+
+    #[test]
+    fn overridden_name_is_shown_in_the_attack_messages() {
+        use crate::output::{set_sink, BufferSink};
+
+        /// A fighter with a title in front of its name.
+        struct Titled(Fighter);
+
+        impl Combatant for Titled {
+            fn entity(&self) -> &Entity {
+                self.0.entity()
+            }
+
+            fn entity_mut(&mut self) -> &mut Entity {
+                self.0.entity_mut()
+            }
+
+            fn name(&self) -> &str {
+                "Kämpfer Hero"
+            }
+        }
+
+        let buffer = BufferSink::new();
+        let previous = set_sink(Box::new(buffer.clone()));
+        // The dodge, damage, crit and counter roll
+        let mut game_rules = rules([3, 4, 3, 1]);
+        let mut player = Titled(Fighter::new(entity("Hero", 10, 0, 4), 1));
+        let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+        player.attack(&mut monster, &mut game_rules);
+        set_sink(previous);
+
+        let output = buffer.contents();
+        assert!(output.contains(&msg(Msg::AttackHit, &[&"Kämpfer Hero", &4])));
+        assert_eq!(player.entity().name(), "Hero");
+        // The log keeps the name of the entity
+        assert_eq!(game_rules.log_entries()[0].actor, "Hero");
+    }
+
+    #[test]
+    fn alive_until_no_life_points_are_left() {
+        let mut fighter = Fighter::new(entity("Hero", 2, 0, 0), 1);
+        assert!(fighter.is_alive());
+        fighter.entity.apply_dmg(1);
+        assert_eq!(fighter.entity.life_points(), 1);
+        assert!(fighter.is_alive() && fighter.entity.is_alive());
+        fighter.entity.apply_dmg(1);
+        assert_eq!(fighter.entity.life_points(), 0);
+        assert!(!fighter.is_alive() && !fighter.entity.is_alive());
+    }
+
+    #[test]
+    fn poison_ticks_for_its_turns() {
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        let mut hero = entity("Hero", 20, 0, 0);
+        hero.poison(3);
+        for _ in 0..POISON_TURNS + 1 {
+            assert!(!hero.tick_poison(&mut game_rules));
+        }
+        assert_eq!(hero.life_points(), 20 - 3 * POISON_TURNS);
+        assert_eq!(game_rules.log_entries().len(), POISON_TURNS);
+
+        let mut weak = entity("Weak", 2, 0, 0);
+        weak.poison(3);
+        assert!(weak.tick_poison(&mut game_rules));
+        assert_eq!(weak.life_points(), 0);
+    }
+
+    #[test]
+    fn burn_halves_every_round_until_it_expires() {
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        let mut hero = entity("Hero", 50, 0, 0);
+        hero.burn(8);
+        let mut ticks = vec![];
+        while hero.has_status() {
+            let life_points = hero.life_points();
+            assert!(!hero.tick_statuses(&mut game_rules));
+            ticks.push(life_points - hero.life_points());
+        }
+        assert_eq!(ticks, [8, 4, 2, 1]);
+        // Expired, so it doesn't tick anymore
+        assert!(!hero.tick_burn(&mut game_rules));
+        assert_eq!(hero.life_points(), 50 - 15);
+        assert_eq!(game_rules.log_entries().len(), 4);
+
+        // Burning again stacks up
+        hero.burn(4);
+        hero.burn(4);
+        assert!(!hero.tick_burn(&mut game_rules));
+        assert_eq!(hero.life_points(), 50 - 15 - 8);
+    }
+
+    #[test]
+    fn custom_difficulty_clamps_the_dice_sides() {
+        assert_eq!(Difficulty::custom(12).to_dice_n(), 12);
+        assert_eq!(Difficulty::custom(1).to_dice_n(), MIN_DICE_SIDES);
+        assert_eq!(Difficulty::Custom(0).to_dice_n(), MIN_DICE_SIDES);
+    }
+
+    #[test]
+    fn difficulty_is_parsed_from_its_name_or_dice_sides() {
+        assert_eq!("easy".parse(), Ok(Difficulty::Easy));
+        assert_eq!("Normal".parse(), Ok(Difficulty::Normal));
+        assert_eq!(" HARD ".parse(), Ok(Difficulty::Hard));
+        assert_eq!("Dynamic".parse(), Ok(Difficulty::Dynamic));
+        assert_eq!("12".parse(), Ok(Difficulty::Custom(12)));
+        assert_eq!("1".parse(), Ok(Difficulty::custom(1)));
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+
+    /// Fights a fighter with 10 life points and 6 dexterity against an orc with 5 dexterity and `strength`,
+    /// rolling the preset `rolls` and choosing the scripted `actions`.
+    fn duel(rolls: &[usize], actions: &[usize], strength: usize) -> FightResult {
+        let mut game_rules = rules(rolls.to_vec());
+        game_rules.set_actions(ScriptedActions::new(actions.to_vec()));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 5, strength), vec![], vec![]);
+        player.fight(&mut monster, &mut game_rules)
+    }
+
+    #[test]
+    fn scripts_end_in_their_outcomes() {
+        // The player goes first and the flee roll succeeds
+        let fled = duel(&[6, 6], &[2], 1);
+        assert_eq!(fled.outcome, FightOutcome::PlayerFled);
+        // The orc goes first and kills the player with its first attack
+        let lost = duel(&[1, 3, 20, 3], &[0], 20);
+        assert_eq!(lost.outcome, FightOutcome::EnemyWon);
+        assert_eq!((fled.rounds, lost.rounds), (1, 1));
+    }
+
+    #[test]
+    fn weapon_breaks_after_its_durability() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 0);
+        let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        player.entity.weapon = Some(Weapon::new(Material::Wood, 0, 0, Some(2), false, None));
+        let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(
+            player.entity.weapon.as_ref().and_then(|w| w.durability),
+            Some(1)
+        );
+        player.attack(&mut monster, &mut game_rules);
+        assert!(player.entity.weapon.is_none());
+        assert_eq!(player.attack_damage(), 4);
+    }
+
+    #[test]
+    fn mage_heals_only_with_enough_mana() {
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        for (mana, healed) in [(5, true), (4, true), (3, false)] {
+            let mut mage = Mage::new(entity("Merlin", 50, 0, 0), 2, mana, 4);
+            mage.entity.weapon = Some(Weapon::new(Material::Wood, 3, 0, None, false, None));
+            mage.entity.apply_dmg(40);
+            assert_eq!(mage.heal(&mut game_rules), healed);
+            if healed {
+                assert_eq!(mage.mana, mana - 4);
+                assert_eq!(mage.entity.life_points(), 10 + DEFAULT_BASE_HEAL + 6);
+            } else {
+                assert_eq!((mage.mana, mage.entity.life_points()), (mana, 10));
+            }
+        }
+    }
+
+    #[test]
+    fn mage_heals_the_base_heal_plus_the_staff_bonus_up_to_the_cap() {
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        let mut mage = Mage::new(entity("Merlin", 50, 0, 0), 2, 20, 4);
+        mage.entity.apply_dmg(40);
+        assert_eq!(mage.get_heal_lp(), DEFAULT_BASE_HEAL);
+        assert!(mage.heal(&mut game_rules));
+        assert_eq!(mage.entity.life_points(), 10 + DEFAULT_BASE_HEAL);
+
+        mage.entity.weapon = Some(Weapon::new(Material::Wood, 3, 0, None, false, None));
+        mage.base_heal = 1;
+        assert_eq!(mage.get_heal_lp(), 1 + 2 * 3);
+        assert!(mage.heal(&mut game_rules));
+        assert_eq!(mage.entity.life_points(), 10 + DEFAULT_BASE_HEAL + 7);
+
+        // Healing never exceeds the max life points
+        mage.base_heal = 100;
+        assert!(mage.heal(&mut game_rules));
+        assert_eq!(mage.entity.life_points(), 50);
+    }
+
+    #[test]
+    fn mana_regenerates_up_to_the_max_mana() {
+        let mut mage = Mage::new(entity("Merlin", 50, 0, 0), 1, DEFAULT_MAX_MANA - 3, 4);
+        mage.start_round();
+        assert_eq!(mage.mana, DEFAULT_MAX_MANA - 1);
+        mage.start_round();
+        mage.start_round();
+        assert_eq!(mage.mana, DEFAULT_MAX_MANA);
+
+        // A mage of a config heals for the default cost
+        let mage: Mage = serde_json::from_value(serde_json::json!({
+            "entity": { "name": "Merlin", "life_points": 50, "dexterity": 0, "strength": 0 },
+            "magic_power": 1
+        }))
+        .unwrap();
+        assert_eq!(
+            (mage.mana, mage.max_mana, mage.heal_cost, mage.base_heal),
+            (0, DEFAULT_MAX_MANA, DEFAULT_HEAL_COST, DEFAULT_BASE_HEAL)
+        );
+    }
+
+    #[test]
+    fn high_dodge_roll_avoids_the_attack() {
+        // Dodge rolls of 6 and 4 bring 20 dexterity to 20 and 13
+        for (rolls, life_points) in [(vec![6], 50), (vec![4, 4, 3, 1], 46)] {
+            let mut game_rules = rules(rolls);
+            let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+            let mut monster = Monster::new(entity("Cat", 50, 20, 1), vec![], vec![]);
+            player.attack(&mut monster, &mut game_rules);
+            assert_eq!(monster.entity.life_points(), life_points);
+        }
+    }
+
+    #[test]
+    fn combat_log_records_the_events_in_order() {
+        // The player goes first, both hit without a crit or counter, then the flee roll succeeds
+        let mut game_rules = rules([6, 3, 4, 3, 1, 3, 1, 3, 1, 6]);
+        // Attack, then flee
+        game_rules.set_actions(ScriptedActions::new([0, 2]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        player.fight(&mut monster, &mut game_rules);
+        let entry = |round, actor: &str, action, amount| LogEntry {
+            round,
+            actor: actor.into(),
+            action,
+            amount,
+        };
+        assert_eq!(
+            game_rules.log_entries(),
+            [
+                entry(1, "Hero", LogAction::Attack, 4),
+                entry(1, "Orc", LogAction::Attack, 1),
+                entry(2, "Hero", LogAction::Flee, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn fight_rounds_yield_the_events_of_every_round() {
+        // Same fight as above: Both hit in the first round, then the player flees
+        let mut game_rules = rules([6, 3, 4, 3, 1, 3, 1, 3, 1, 6]);
+        game_rules.set_actions(ScriptedActions::new([0, 2]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        let mut rounds = player.fight_rounds(&mut monster, &mut game_rules);
+        assert_eq!(rounds.result(), None);
+        let events: Vec<FightEvent> = rounds.by_ref().collect();
+        assert_eq!(
+            rounds.result(),
+            Some(FightResult {
+                outcome: FightOutcome::PlayerFled,
+                rounds: 2
+            })
+        );
+
+        let entry = |round, actor: &str, action, amount| LogEntry {
+            round,
+            actor: actor.into(),
+            action,
+            amount,
+        };
+        assert_eq!(
+            events,
+            [
+                FightEvent::Initiative(Ordering::Player),
+                FightEvent::RoundStart(1),
+                FightEvent::Status(0),
+                FightEvent::RoundEnd(RoundEvent {
+                    round: 1,
+                    actions: vec![
+                        entry(1, "Hero", LogAction::Attack, 4),
+                        entry(1, "Orc", LogAction::Attack, 1),
+                    ],
+                    player_life_points: vec![9],
+                    enemy_life_points: 96,
+                    outcome: None,
+                }),
+                FightEvent::RoundStart(2),
+                FightEvent::Status(0),
+                FightEvent::RoundEnd(RoundEvent {
+                    round: 2,
+                    actions: vec![entry(2, "Hero", LogAction::Flee, 0)],
+                    player_life_points: vec![9],
+                    enemy_life_points: 96,
+                    outcome: Some(FightOutcome::PlayerFled),
+                }),
+            ]
+        );
+
+        // Nothing is revealed by the rounds themselves, only by the terminal frontend
+        let buffer = crate::output::BufferSink::new();
+        let previous = crate::output::set_sink(Box::new(buffer.clone()));
+        let mut game_rules = rules([6, 3, 4, 3, 1, 3, 1, 3, 1, 6]);
+        game_rules.set_actions(ScriptedActions::new([0, 2]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        player
+            .fight_rounds(&mut monster, &mut game_rules)
+            .for_each(drop);
+        crate::output::set_sink(previous);
+        assert!(!buffer.contents().contains(&msg(Msg::RoundStart, &[&1])));
+        assert!(!buffer.contents().contains(&msg(
+            Msg::FirstAttacker,
+            &[&format!("{:?}", Ordering::Player)]
+        )));
+    }
+
+    #[test]
+    fn two_handed_weapon_hits_harder_but_slows_down() {
+        let mut hero = entity("Hero", 10, 8, 0);
+        hero.weapon = Some(Weapon::new(Material::Wood, 0, 0, None, false, None));
+        let damage = hero.weapon.as_ref().unwrap().calc_damage();
+        assert_eq!(hero.effective_dexterity(), 8);
+
+        hero.weapon = Some(Weapon::new(Material::Wood, 0, 0, None, true, None));
+        assert_eq!(
+            hero.weapon.as_ref().unwrap().calc_damage(),
+            damage + TWO_HANDED_DAMAGE_BONUS
+        );
+        assert_eq!(hero.effective_dexterity(), 8 - TWO_HANDED_DEXTERITY_PENALTY);
+    }
+
+    #[test]
+    fn rogue_backstabs_only_with_the_first_hit() {
+        let mut game_rules = rules([3, 10, 3, 1, 3, 4, 3, 1]);
+        let mut rogue = Rogue::new(entity("Shade", 10, 0, 4), 6);
+        let mut monster = Monster::new(entity("Orc", 50, 0, 1), vec![], vec![]);
+        assert_eq!(rogue.attack_damage(), 10);
+        rogue.attack(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points(), 40);
+        assert_eq!(rogue.attack_damage(), 4);
+        rogue.attack(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points(), 40 - 4 - COMBO_DAMAGE_BONUS);
+        rogue.start_fight();
+        assert_eq!(rogue.attack_damage(), 10);
+    }
+
+    #[test]
+    fn surviving_defender_may_counter_with_half_the_damage() {
+        // The dodge, damage, crit and counter roll, then the dodge, damage and crit roll of the counter
+        for (rolls, life_points) in [
+            (vec![3, 4, 3, 6, 3, 8, 3], 20 - 8 / COUNTER_DAMAGE_DIVISOR),
+            (vec![3, 4, 3, 1], 20),
+        ] {
+            let mut game_rules = rules(rolls);
+            let mut player = Fighter::new(entity("Hero", 20, 0, 4), 1);
+            let mut monster = Monster::new(entity("Orc", 50, 0, 8), vec![], vec![]);
+            player.attack(&mut monster, &mut game_rules);
+            assert_eq!(player.entity.life_points(), life_points);
+            assert!(!game_rules.countering);
+        }
+    }
+
+    #[test]
+    fn swapping_the_weapon_changes_the_attack_damage() {
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        // Attack, defend, swap the weapon, flee; then the first spare weapon
+        game_rules.set_actions(ScriptedActions::new([2, 0]));
+        let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        player.entity.level = Material::Diamond.min_level();
+        player.entity.weapon = Some(Weapon::new(Material::Wood, 0, 0, None, false, None));
+        player.entity.inventory = vec![Weapon::new(Material::Diamond, 0, 0, None, false, None)];
+        assert_eq!(player.attack_damage(), 1 + 4);
+        let mut monster = Monster::new(entity("Orc", 10, 0, 1), vec![], vec![]);
+        assert!(!player.select_action(&mut monster, &mut game_rules));
+        assert_eq!(player.attack_damage(), 6 + 4);
+        // The old weapon went into the inventory and the swap took the turn
+        assert_eq!(player.entity.inventory().len(), 1);
+        assert_eq!(player.entity.inventory()[0].calc_damage(), 1);
+        assert_eq!(monster.entity.life_points(), 10);
+        assert_eq!(game_rules.choices(), [2, 0]);
+
+        // Without a weapon the spare one is equipped and the inventory is empty
+        player.entity.weapon = None;
+        player.entity.swap_weapon(0);
+        assert_eq!(player.attack_damage(), 1 + 4);
+        assert!(player.entity.inventory().is_empty());
+    }
+
+    #[test]
+    fn low_level_player_cant_equip_a_high_tier_material() {
+        use crate::output::{set_sink, BufferSink};
+
+        let weapon = |material| Weapon::new(material, 0, 0, None, false, None);
+        let buffer = BufferSink::new();
+        let previous = set_sink(Box::new(buffer.clone()));
+        let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        player.entity.weapon = Some(weapon(Material::Diamond));
+        player.entity.inventory = vec![weapon(Material::Iron), weapon(Material::Stone)];
+        assert!(!player.entity.can_wield(&weapon(Material::Diamond)));
+
+        // The diamond goes into the inventory, the stone is the strongest material of level 1
+        player.entity.unequip_locked_weapons();
+        assert_eq!(player.entity.weapon(), Some(&weapon(Material::Stone)));
+        assert_eq!(
+            player.entity.inventory(),
+            [weapon(Material::Iron), weapon(Material::Diamond)]
+        );
+        assert!(buffer
+            .contents()
+            .contains(&msg(Msg::WeaponLocked, &[&"Hero", &"Diamond", &5])));
+
+        // Swapping to the diamond (marked as locked) is rejected, so the defend is chosen instead
+        let mut game_rules = GameRules::new(Difficulty::Normal);
+        let (recorder, offered) = Recorder::new([2, 1, 1]);
+        game_rules.set_actions(recorder);
+        let mut monster = Monster::new(entity("Orc", 10, 0, 1), vec![], vec![]);
+        assert!(!player.select_action(&mut monster, &mut game_rules));
+        assert_eq!(player.entity.weapon(), Some(&weapon(Material::Stone)));
+        assert!(player.entity.defending);
+        assert_eq!(
+            offered.borrow()[1],
+            [
+                msg(Msg::WeaponOptionLocked, &[&3, &2]),
+                msg(Msg::WeaponOptionLocked, &[&6, &5]),
+            ]
+        );
+
+        // A looted diamond isn't equipped either, but kept in the inventory
+        player.entity.inventory.clear();
+        player
+            .entity
+            .take_loot(Loot::Weapon(weapon(Material::Diamond)), Proficiency::FULL);
+        assert_eq!(player.entity.weapon(), Some(&weapon(Material::Stone)));
+        assert_eq!(player.entity.inventory(), [weapon(Material::Diamond)]);
+
+        // Levelled up far enough the diamond can be equipped
+        player.entity.level = 5;
+        game_rules.set_actions(ScriptedActions::new([2, 0]));
+        assert!(!player.select_action(&mut monster, &mut game_rules));
+        assert_eq!(player.entity.weapon(), Some(&weapon(Material::Diamond)));
+        set_sink(previous);
+    }
+
+    #[test]
+    fn off_hand_adds_half_its_damage() {
+        let iron = || Some(Weapon::new(Material::Iron, 0, 0, None, false, None));
+        let mut single = Fighter::new(entity("Hero", 10, 0, 4), 2);
+        single.entity.weapon = iron();
+        let mut dual = Fighter::new(entity("Hero", 10, 0, 4), 2);
+        dual.entity.weapon = iron();
+        dual.entity.off_hand = Some(Weapon::new(Material::Diamond, 0, 0, None, false, None));
+        // (3 + 4) * 2 single-wielded, (3 + 6 / 2 + 4) * 2 dual-wielded
+        assert_eq!((single.attack_damage(), dual.attack_damage()), (14, 20));
+        assert_eq!(dual.weapon_attack_damage(), 12);
+
+        // Without a main weapon the off-hand still adds its part
+        let mut monster = Monster::new(entity("Orc", 10, 0, 4), vec![], vec![]);
+        assert_eq!(monster.attack_damage(), 4);
+        monster.entity.off_hand = iron();
+        assert_eq!(
+            monster.attack_damage(),
+            4 + 3 * OFF_HAND_DAMAGE_PERCENT / 100
+        );
+    }
+
+    #[test]
+    fn thorns_reflect_damage_to_the_attacker() {
+        // Dodge, damage, no crit and no counter
+        let mut game_rules = rules([3, 10, 3, 1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 10), 1);
+        let mut monster = Monster::new(entity("Hedgehog", 100, 0, 1), vec![], vec![]);
+        monster.entity.thorns = 50;
+        assert!(!player.attack(&mut monster, &mut game_rules));
+        assert_eq!(monster.entity.life_points(), 100 - 10);
+        assert_eq!(player.entity.life_points(), 50 - 5);
+
+        // Reflected damage defeating the attacker ends the fight without a counter roll
+        let mut player = Fighter::new(entity("Hero", 5, 0, 10), 1);
+        assert!(player.attack(&mut monster, &mut rules([3, 10, 3])));
+        assert_eq!(player.entity.life_points(), 0);
+
+        // A defeated enemy doesn't reflect anymore
+        let mut player = Fighter::new(entity("Hero", 5, 0, 10), 1);
+        let mut monster = Monster::new(entity("Hedgehog", 10, 0, 1), vec![], vec![]);
+        monster.entity.thorns = 100;
+        assert!(player.attack(&mut monster, &mut rules([3, 10, 3])));
+        assert_eq!(player.entity.life_points(), 5);
+    }
+
+    #[test]
+    fn resistance_halves_the_scaled_weapon_damage() {
+        for (resistances, life_points) in [(vec![], 50 - 14), (vec![Material::Iron], 50 - 14 + 3)] {
+            let mut game_rules = rules([3, 14, 3, 1]);
+            let mut player = Fighter::new(entity("Hero", 10, 0, 4), 2);
+            player.entity.weapon = Some(Weapon::new(Material::Iron, 0, 0, None, false, None));
+            assert_eq!(
+                (player.attack_damage(), player.weapon_attack_damage()),
+                (14, 6)
+            );
+            let mut monster = Monster::new(entity("Golem", 50, 0, 1), vec![], vec![]);
+            monster.entity.resistances = resistances;
+            player.attack(&mut monster, &mut game_rules);
+            assert_eq!(monster.entity.life_points(), life_points);
+        }
+    }
+
+    #[test]
+    fn mage_can_use_a_staff_fully_but_not_an_iron_weapon() {
+        let mut mage = Mage::new(entity("Merlin", 10, 0, 4), 2, 0, DEFAULT_HEAL_COST);
+        mage.entity.weapon = Some(Weapon::new(Material::Wood, 3, 0, None, false, None));
+        assert_eq!(mage.attack_damage(), 1 + 3 + 4);
+        assert_eq!(mage.get_heal_lp(), DEFAULT_BASE_HEAL + 2 * 3);
+
+        // Only half of the heavy material counts, the spell power still fully
+        mage.entity.weapon = Some(Weapon::new(Material::Iron, 3, 0, None, false, None));
+        assert_eq!(mage.attack_damage(), 3 / 2 + 3 + 4);
+        assert_eq!(mage.weapon_attack_damage(), 3 / 2 + 3);
+        assert_eq!(mage.get_heal_lp(), DEFAULT_BASE_HEAL + 2 * 3);
+        // Other classes use the iron weapon fully
+        let mut fighter = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        fighter.entity.weapon = Some(Weapon::new(Material::Iron, 0, 0, None, false, None));
+        assert_eq!(fighter.attack_damage(), 3 + 4);
+    }
+
+    #[test]
+    fn magic_weak_monster_takes_extra_damage_from_a_mage() {
+        let staff = || Some(Weapon::new(Material::Wood, 3, 0, None, false, None));
+        let mut mage = Mage::new(entity("Merlin", 10, 0, 6), 1, 0, DEFAULT_HEAL_COST);
+        mage.entity.weapon = staff();
+        let mut fighter = Fighter::new(entity("Hero", 10, 0, 6), 1);
+        fighter.entity.weapon = staff();
+        assert_eq!(
+            (mage.damage_type(), mage.attack_damage()),
+            (DamageType::Magic, 10)
+        );
+        // A fighter can't use the spell power of the staff, see `Proficiency`
+        assert_eq!(
+            (fighter.damage_type(), fighter.attack_damage()),
+            (DamageType::Physical, 7)
+        );
+
+        let monster = || {
+            let mut monster = Monster::new(entity("Wraith", 50, 0, 1), vec![], vec![]);
+            monster.entity.magic_weakness = 150;
+            monster
+        };
+        let mut wraith = monster();
+        mage.attack(&mut wraith, &mut rules([3, 10, 3, 1]));
+        assert_eq!(wraith.entity.life_points(), 50 - 15);
+        let mut wraith = monster();
+        fighter.attack(&mut wraith, &mut rules([3, 7, 3, 1]));
+        assert_eq!(wraith.entity.life_points(), 50 - 7);
+    }
+
+    #[test]
+    fn failed_autosave_is_kept_for_the_caller() {
+        let dir = std::env::temp_dir().join(format!(
+            "simple-fantasy-game-{}-missing",
+            std::process::id()
+        ));
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 2);
+        game_rules.set_autosave(dir.join("save.json"));
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        player.fight(
+            &mut Monster::new(entity("Goblin", 4, 0, 1), vec![], vec![]),
+            &mut game_rules,
+        );
+        assert!(game_rules.take_autosave_error().is_some());
+        assert!(game_rules.take_autosave_error().is_none());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn game_rules_keep_the_difficulty_when_serialized() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Hard, 3);
+        game_rules.set_max_rounds(20);
+        game_rules.set_tie_break(TieBreak::Reroll);
+        game_rules.set_config(&serde_json::json!({ "players": [] }));
+        let json = serde_json::to_string(&game_rules).unwrap();
+        let mut loaded: GameRules = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.difficulty(), Difficulty::Hard);
+        assert_eq!(loaded.seed(), Some(3));
+        assert_eq!(loaded.max_rounds(), 20);
+        assert_eq!(loaded.tie_break(), TieBreak::Reroll);
+        assert_eq!(loaded.config(), game_rules.config());
+        // The dice is seeded again, so it rolls the same
+        let rolls: Vec<_> = (0..5).map(|_| game_rules.dice.roll()).collect();
+        let loaded_rolls: Vec<_> = (0..5).map(|_| loaded.dice.roll()).collect();
+        assert_eq!(rolls, loaded_rolls);
+
+        // The dice sides adapted by a dynamic difficulty are kept
+        let mut game_rules = GameRules::with_seed(Difficulty::Dynamic, 3);
+        game_rules.dice.n = 8;
+        let json = serde_json::to_string(&game_rules).unwrap();
+        let loaded: GameRules = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.dice_sides(), 8);
+
+        // Everything but the difficulty is optional
+        let loaded: GameRules = serde_json::from_str(r#"{ "difficulty": "Easy" }"#).unwrap();
+        assert_eq!(loaded.difficulty(), Difficulty::Easy);
+        assert_eq!(
+            (loaded.seed(), loaded.max_rounds()),
+            (None, DEFAULT_MAX_ROUNDS)
+        );
+    }
+
+    #[test]
+    fn dominant_win_raises_a_dynamic_difficulty() {
+        // Initiative, dodge, damage and crit roll: The hero defeats the orc without a scratch
+        let mut game_rules =
+            GameRules::with_random(Difficulty::Dynamic, FixedSequence::new([6, 1, 4, 2]));
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 1, 0, 1), vec![], vec![]);
+        assert_eq!(game_rules.dice_sides(), 6);
+        player.fight(&mut monster, &mut game_rules);
+        assert_eq!(game_rules.dice_sides(), 7);
+
+        // A win by a hair lowers it, within the bounds
+        player.entity.apply_dmg(9);
+        game_rules.adapt_difficulty(FightOutcome::PlayerWon, &[&player.entity]);
+        assert_eq!(game_rules.dice_sides(), 6);
+        for _ in 0..10 {
+            game_rules.adapt_difficulty(FightOutcome::EnemyWon, &[&player.entity]);
+        }
+        assert_eq!(game_rules.dice_sides(), *DYNAMIC_DICE_SIDES.start());
+
+        // Other difficulties don't adapt
+        let mut game_rules = rules([]);
+        let player = Fighter::new(entity("Hero", 10, 6, 4), 1);
+        game_rules.adapt_difficulty(FightOutcome::PlayerWon, &[&player.entity]);
+        assert_eq!(game_rules.dice_sides(), 6);
+    }
+
+    #[test]
+    fn tie_break_decides_the_order_on_equal_dexterity() {
+        let hero = entity("Hero", 10, 6, 1);
+        let orc = entity("Orc", 10, 3, 1);
+        // A roll of 3 of 6 halves the dexterity of the hero to the one of the orc
+        let ordering = |tie_break: TieBreak, rolls: &[usize]| {
+            let mut game_rules = rules(rolls.to_vec());
+            game_rules.set_tie_break(tie_break);
+            roll_initiative(&mut game_rules, &hero, &orc)
+        };
+        assert!(ordering(TieBreak::Defender, &[3]) == Ordering::Enemy);
+        assert!(ordering(TieBreak::Initiator, &[3]) == Ordering::Player);
+        assert!(ordering(TieBreak::Reroll, &[3, 3, 4]) == Ordering::Player);
+        assert!(ordering(TieBreak::Reroll, &[3, 2]) == Ordering::Enemy);
+        // Tied every time, the defender attacks first after the last reroll
+        assert!(ordering(TieBreak::Reroll, &[3; MAX_INITIATIVE_REROLLS + 1]) == Ordering::Enemy);
+        // Without a tie the policy doesn't matter
+        assert!(ordering(TieBreak::Defender, &[4]) == Ordering::Player);
+        assert!(ordering(TieBreak::Initiator, &[2]) == Ordering::Enemy);
+    }
+
+    #[test]
+    fn tie_break_is_parsed_ignoring_the_case() {
+        assert_eq!("Initiator".parse(), Ok(TieBreak::Initiator));
+        assert_eq!("defender".parse(), Ok(TieBreak::Defender));
+        assert_eq!(" REROLL ".parse(), Ok(TieBreak::Reroll));
+        assert!("coin".parse::<TieBreak>().is_err());
+    }
+
+    #[test]
+    fn rerolled_initiative_changes_the_order_every_round() {
+        let first_actors = |reroll_initiative: bool| -> Vec<String> {
+            let mut game_rules = GameRules::with_seed(Difficulty::Normal, 29);
+            game_rules.set_actions(AutoPlayer::default());
+            game_rules.set_reroll_initiative(reroll_initiative);
+            game_rules.set_max_rounds(4);
+            let mut player = Fighter::new(entity("Hero", 200, 6, 1), 1);
+            let mut monster = Monster::new(entity("Orc", 200, 3, 1), vec![], vec![]);
+            player.fight(&mut monster, &mut game_rules);
+            (1..=4)
+                .filter_map(|round| {
+                    let log = game_rules.log_entries();
+                    let first = log.iter().find(|entry| entry.round == round)?;
+                    Some(first.actor.clone())
+                })
+                .collect()
+        };
+        assert_eq!(first_actors(true), ["Hero", "Orc", "Hero", "Orc"]);
+        // Rolled once, the order stays the same
+        assert_eq!(first_actors(false), ["Hero"; 4]);
+    }
+
+    /// Chooses like `ScriptedActions` and records the options offered for every choice.
+    struct Recorder {
+        offered: Rc<RefCell<Vec<Vec<String>>>>,
+        script: ScriptedActions,
+    }
+
+    impl Recorder {
+        /// Creates a recorder choosing the `indices` and the options it will record.
+        fn new(indices: impl IntoIterator<Item = usize>) -> (Self, Rc<RefCell<Vec<Vec<String>>>>) {
+            let offered = Rc::default();
+            let recorder = Self {
+                offered: Rc::clone(&offered),
+                script: ScriptedActions::new(indices),
+            };
+            (recorder, offered)
+        }
+    }
+
+    impl ActionSource for Recorder {
+        fn choose(&mut self, prompt: &str, options: &[&str]) -> usize {
+            let recorded = options.iter().map(|o| o.to_string()).collect();
+            self.offered.borrow_mut().push(recorded);
+            self.script.choose(prompt, options)
+        }
+    }
+
+    /// Counts the offered options starting with `key` for every choice.
+    fn offered_count(offered: &RefCell<Vec<Vec<String>>>, key: Msg) -> Vec<usize> {
+        offered
+            .borrow()
+            .iter()
+            .map(|options| options.iter().filter(|o| o.starts_with(text(key))).count())
+            .collect()
+    }
+
+    #[test]
+    fn potion_heals_until_none_is_left() {
+        // The player goes first and drinks the potion, the orc hits without a counter, then the player flees
+        let mut game_rules = rules([6, 3, 1, 3, 1, 6]);
+        let (recorder, offered) = Recorder::new([2, 2]);
+        game_rules.set_actions(recorder);
+        let mut player = Fighter::new(entity("Hero", 50, 6, 4), 1);
+        player.entity.potions = 1;
+        player.entity.apply_dmg(40);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(result.outcome, FightOutcome::PlayerFled);
+        assert_eq!(player.entity.life_points(), 10 + POTION_HEAL - 1);
+        assert_eq!(player.entity.potions, 0);
+        assert_eq!(offered_count(&offered, Msg::Potion), [1, 0]);
+    }
+
+    #[test]
+    fn antidote_clears_an_active_poison() {
+        // The player goes first and drinks the antidote, the orc hits without a counter, then the player flees
+        let mut game_rules = rules([6, 3, 1, 3, 1, 6]);
+        let (recorder, offered) = Recorder::new([2, 2]);
+        game_rules.set_actions(recorder);
+        let mut player = Fighter::new(entity("Hero", 50, 6, 4), 1);
+        player.entity.antidotes = 2;
+        player.entity.poison(5);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(result.outcome, FightOutcome::PlayerFled);
+        // The poison ticks once before the antidote, but not anymore after it
+        assert_eq!(player.entity.life_points(), 50 - 5 - 1);
+        assert!(!player.entity.has_status());
+        assert_eq!(player.entity.antidotes, 1);
+        // Only offered while a status is active
+        assert_eq!(offered_count(&offered, Msg::Antidote), [1, 0]);
+    }
+
+    /// Attacks an orc with 50 life points once with a wood weapon of the `enchantment`,
+    /// without a crit, stun or counter, and returns the wounded attacker and the orc.
+    fn enchanted_attack(enchantment: Enchantment) -> (Fighter, Monster) {
+        let mut game_rules = rules([3, 5, 3, 1, 1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 4), 1);
+        player.entity.weapon = Some(Weapon::new(
+            Material::Wood,
+            0,
+            0,
+            None,
+            false,
+            Some(enchantment),
+        ));
+        player.entity.apply_dmg(10);
+        let mut monster = Monster::new(entity("Orc", 50, 6, 1), vec![], vec![]);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points(), 45);
+        (player, monster)
+    }
+
+    #[test]
+    fn enchantments_apply_their_effects_on_hit() {
+        let (_, monster) = enchanted_attack(Enchantment::Fire);
+        assert_eq!(monster.entity.burn_stack, FIRE_BURN_DMG);
+        assert_eq!(monster.entity.poison_turns, 0);
+        let (_, monster) = enchanted_attack(Enchantment::Frost);
+        assert!(monster.entity.frozen);
+        assert_eq!(
+            monster.entity.effective_dexterity(),
+            6 - FROST_DEXTERITY_PENALTY
+        );
+        let (player, monster) = enchanted_attack(Enchantment::Vampiric);
+        assert_eq!(player.entity.life_points(), 40 + 5 / VAMPIRIC_HEAL_DIVISOR);
+        assert!(!monster.entity.frozen);
+        assert_eq!(monster.entity.poison_turns, 0);
+    }
+
+    #[test]
+    fn stunned_monster_skips_its_next_turn() {
+        // Dodge, damage, no crit, stun, then the attack of the monster without a crit or counter
+        let mut game_rules = rules([3, 20, 3, 6, 3, 1, 3, 1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 4), STUN_ENDURANCE);
+        let mut monster = Monster::new(entity("Orc", 100, 0, 1), vec![], vec![]);
+        assert!(!player.attack(&mut monster, &mut game_rules));
+        assert!(monster.entity.stunned);
+
+        assert!(!monster.take_turn(&mut player, &mut game_rules));
+        assert_eq!(player.entity.life_points(), 50);
+        assert!(!monster.entity.stunned);
+        // The stun lasts a single turn
+        assert!(!monster.take_turn(&mut player, &mut game_rules));
+        assert_eq!(player.entity.life_points(), 49);
+    }
+
+    #[test]
+    fn damage_totals_count_without_the_overkill() {
+        let mut game_rules = rules([3, 4, 3, 1, 3, 4, 3, 1, 3, 4, 3]);
+        let mut player = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 15, 0, 1), vec![], vec![]);
+        // 4, 6 and 8 damage with the combo, of which only 5 are left for the last one
+        for defeated in [false, false, true] {
+            assert_eq!(player.attack(&mut monster, &mut game_rules), defeated);
+        }
+        assert_eq!(player.entity.total_damage_dealt(), 15);
+        assert_eq!(monster.entity.total_damage_taken(), 15);
+        assert_eq!(player.entity.total_damage_taken(), 0);
+    }
+
+    #[test]
+    fn monster_stats_scale_with_the_difficulty() {
+        for (difficulty, life_points, strength) in [
+            (Difficulty::Easy, 75, 7),
+            (Difficulty::Normal, 100, 10),
+            (Difficulty::Hard, 125, 12),
+            (Difficulty::custom(20), 100, 10),
+        ] {
+            let mut orc = entity("Orc", 100, 5, 10);
+            orc.scale_stats(difficulty.stat_modifier());
+            assert_eq!(
+                (orc.life_points(), orc.max_life_points()),
+                (life_points, life_points)
+            );
+            assert_eq!((orc.strength, orc.dexterity), (strength, 5));
+        }
+        let mut rat = entity("Rat", 1, 0, 0);
+        rat.scale_stats(Difficulty::Easy.stat_modifier());
+        assert_eq!(rat.life_points(), 1);
+    }
+
+    #[test]
+    fn failed_flee_has_a_cooldown() {
+        // The flee fails, the orc hits without a counter every round, then the flee succeeds after the cooldown
+        let mut game_rules = rules([6, 1, 3, 1, 3, 1, 3, 1, 3, 1, 3, 1, 3, 1, 6]);
+        // Flee, defend twice, flee
+        let (recorder, offered) = Recorder::new([2, 1, 1, 2]);
+        game_rules.set_actions(recorder);
+        let mut player = Fighter::new(entity("Hero", 50, 6, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(
+            (result.outcome, result.rounds),
+            (FightOutcome::PlayerFled, 4)
+        );
+        let flee_options: Vec<String> = offered
+            .borrow()
+            .iter()
+            .map(|options| options.last().unwrap().clone())
+            .collect();
+        assert_eq!(
+            flee_options,
+            [
+                msg(Msg::FleeOption, &[&"17"]),
+                msg(Msg::FleeCooldownOption, &[&2]),
+                msg(Msg::FleeCooldownOption, &[&1]),
+                msg(Msg::FleeOption, &[&"17"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn boss_hits_harder_in_the_next_phase() {
+        let mut game_rules = rules([3, 15, 3, 1]);
+        let mut boss = Monster::new(entity("Dragon", 100, 0, 10), vec![75], vec![]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 4), 1);
+        boss.entity.apply_dmg(30);
+        assert_eq!(boss.attack_damage(), 10);
+        boss.select_action(&mut player, &mut game_rules);
+        assert_eq!(boss.phase, 2);
+        assert_eq!(boss.attack_damage(), 10 + PHASE_STRENGTH_BONUS);
+        assert_eq!(player.entity.life_points(), 50 - 15);
+    }
+
+    #[test]
+    fn damage_is_rolled_within_its_range() {
+        let range = DamageRange::new(20);
+        assert_eq!((range.min, range.max), (15, 25));
+        assert_eq!(range.to_string(), "15–25");
+        assert_eq!(DamageRange::new(3).to_string(), "3");
+
+        let mut dice = Dice::with_seed(6, 1);
+        let rolls: Vec<usize> = (0..100).map(|_| dice.roll_damage(20)).collect();
+        assert!(rolls.iter().all(|dmg| (15..=25).contains(dmg)));
+        // Both ends of the range are rolled at some point
+        assert!(rolls.contains(&15) && rolls.contains(&25));
+        let mut dice = Dice::with_random(6, FixedSequence::new([17]));
+        assert_eq!(dice.roll_damage(20), 17);
+    }
+
+    /// Fights the always attacking `party` against a troll, which beats each of them alone.
+    fn fight_troll(party: &mut [Fighter]) -> FightResult {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 1);
+        game_rules.set_actions(ScriptedActions::new([0; 100]));
+        let mut troll = Monster::new(entity("Troll", 60, 5, 12), vec![], vec![]);
+        fight_party(party, &mut troll, &mut game_rules)
+    }
+
+    #[test]
+    fn party_beats_a_monster_which_beats_each_member() {
+        let members = || {
+            [
+                Fighter::new(entity("Hero", 40, 5, 6), 2),
+                Fighter::new(entity("Squire", 40, 5, 6), 2),
+            ]
+        };
+        for member in members() {
+            let name = member.entity.name().to_string();
+            let result = fight_troll(&mut [member]);
+            assert_eq!(result.outcome, FightOutcome::EnemyWon, "{name}");
+        }
+        assert_eq!(fight_troll(&mut members()).outcome, FightOutcome::PlayerWon);
+    }
+
+    #[test]
+    fn party_rounds_tell_the_status_before_every_member() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 1);
+        game_rules.set_actions(ScriptedActions::new([0; 100]));
+        let mut party = [
+            Fighter::new(entity("Hero", 40, 5, 6), 2),
+            Fighter::new(entity("Squire", 40, 5, 6), 2),
+        ];
+        let mut troll = Monster::new(entity("Troll", 60, 5, 12), vec![], vec![]);
+        let mut rounds = fight_party_rounds(&mut party, &mut troll, &mut game_rules);
+        let events: Vec<FightEvent> = rounds.by_ref().collect();
+        let result = rounds.result().unwrap();
+        assert_eq!(result.outcome, FightOutcome::PlayerWon);
+
+        // A party goes first without an initiative
+        assert_eq!(
+            events[..4],
+            [
+                FightEvent::RoundStart(1),
+                FightEvent::Status(0),
+                FightEvent::Status(1),
+                FightEvent::RoundEnd(RoundEvent {
+                    round: 1,
+                    actions: game_rules.log_entries()[..4].to_vec(),
+                    player_life_points: vec![40, 40],
+                    enemy_life_points: 50,
+                    outcome: None,
+                }),
+            ]
+        );
+        let ends = events
+            .iter()
+            .filter(|event| matches!(event, FightEvent::RoundEnd(_)));
+        assert_eq!(ends.count(), result.rounds);
+        assert!(!game_rules.party);
+    }
+
+    #[test]
+    fn throw_dice_succeeds_only_on_the_highest_side() {
+        let mut dice = Dice::with_random(6, FixedSequence::new([6, 5, 1, 6]));
+        let throws: Vec<bool> = (0..4).map(|_| dice.throw_dice()).collect();
+        assert_eq!(throws, [true, false, false, true]);
+        let mut dice = Dice::with_random(6, FixedSequence::new([3, 6]));
+        assert_eq!(
+            (dice.apply_dice_roll(10), dice.apply_dice_roll(10)),
+            (5, 10)
+        );
+    }
+
+    #[test]
+    fn empirical_means_are_near_the_theoretical_values() {
+        let mut dice = Dice::with_seed(6, 0);
+        // Rolls of 1 to 6 applied to 12 are 2, 4, ..., 12 with a mean of 7
+        let mean = dice.expected_value(12, 10_000);
+        assert!((mean - 7.0).abs() < 0.2, "{mean}");
+        let flee = dice.flee_expected(10_000);
+        assert!((flee - dice.flee_probability()).abs() < 0.02, "{flee}");
+        assert_eq!(dice.expected_value(12, 0), 0.0);
+    }
+
+    #[test]
+    fn heavy_weapons_make_fleeing_harder() {
+        use crate::output::{set_sink, SilentSink};
+
+        let unarmed = Fighter::new(entity("Hero", 10, 0, 1), 1);
+        let mut armed = unarmed.clone();
+        // A two-handed iron greatsword weighs 6, which holds back 30% of the flees
+        armed.entity.weapon = Some(Weapon::new(Material::Iron, 0, 0, None, true, None));
+        assert_eq!(unarmed.entity.flee_penalty(), 0);
+        assert_eq!(armed.entity.flee_penalty(), 6 * FLEE_WEIGHT_PENALTY_PERCENT);
+        armed.entity.inventory = vec![armed.entity.weapon.clone().unwrap(); 2];
+        assert_eq!(armed.entity.flee_penalty(), MAX_FLEE_WEIGHT_PENALTY);
+        armed.entity.inventory.clear();
+
+        let game_rules = rules([]);
+        assert_eq!(
+            unarmed.flee_option(&game_rules),
+            msg(Msg::FleeOption, &[&"17"])
+        );
+        assert_eq!(
+            armed.flee_option(&game_rules),
+            msg(Msg::FleeOption, &[&"12"])
+        );
+
+        // Every flee of both with the same seed, fleeing armed succeeds less often
+        let previous = set_sink(Box::new(SilentSink));
+        let flee_share = |mut fighter: Fighter| {
+            let mut game_rules = GameRules::with_seed(Difficulty::Normal, 7);
+            let successes = (0..2000).filter(|_| fighter.flee(&mut game_rules)).count();
+            successes as f64 / 2000.0
+        };
+        let (unarmed, armed) = (flee_share(unarmed), flee_share(armed));
+        set_sink(previous);
+        assert!((unarmed - 1.0 / 6.0).abs() < 0.03, "{unarmed}");
+        assert!((armed - 0.7 / 6.0).abs() < 0.03, "{armed}");
+        assert!(armed < unarmed);
+    }
+
+    #[test]
+    fn flee_probability_is_one_over_the_dice_sides() {
+        for (n, probability) in [(3, 1.0 / 3.0), (6, 1.0 / 6.0), (9, 1.0 / 9.0)] {
+            assert_eq!(Dice::with_seed(n, 0).flee_probability(), probability);
+        }
+        let game_rules = GameRules::with_seed(Difficulty::Easy, 0);
+        assert_eq!(game_rules.dice.flee_probability(), 1.0 / 3.0);
+        assert_eq!(Difficulty::Easy.flee_chance(), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn heavy_materials_weigh_their_full_modifier() {
+        // Wood, Stone, Iron, Gold, MagicOre and Diamond
+        let weights: Vec<usize> = Material::all().map(|material| material.weight()).collect();
+        assert_eq!(weights, [1, 1, 3, 2, 3, 6]);
+        let copper = Material::Custom {
+            name: "Copper".into(),
+            modifier: 5,
+        };
+        assert_eq!(copper.weight(), 3);
+        // So the heavy materials are the heaviest of their damage
+        assert!(Material::Iron.weight() > Material::Gold.weight());
+        assert!(Material::Diamond.weight() > Material::MagicOre.weight());
+    }
+
+    #[test]
+    fn regeneration_heals_every_round_up_to_the_cap() {
+        let mut game_rules = rules([]);
+        let mut troll = entity("Troll", 20, 0, 0);
+        troll.regen = 5;
+        troll.apply_dmg(7);
+        let life_points: Vec<usize> = (0..3)
+            .map(|_| {
+                troll.begin_round(&mut game_rules);
+                troll.life_points()
+            })
+            .collect();
+        assert_eq!(life_points, [18, 20, 20]);
+        assert_eq!(game_rules.log_entries().len(), 2);
+
+        troll.apply_dmg(20);
+        troll.begin_round(&mut game_rules);
+        assert_eq!(troll.life_points(), 0);
+    }
+
+    #[test]
+    fn custom_material_adds_its_modifier() {
+        let material: Material =
+            serde_json::from_str(r#"{ "Custom": { "name": "Mithril", "modifier": 8 } }"#).unwrap();
+        assert_eq!(
+            material,
+            Material::Custom {
+                name: "Mithril".into(),
+                modifier: 8
+            }
+        );
+        let mut fighter = Fighter::new(entity("Hero", 10, 0, 4), 1);
+        fighter.entity.weapon = Some(Weapon::new(material.clone(), 0, 0, None, false, None));
+        assert_eq!(fighter.attack_damage(), 8 + 4);
+        // Not heavy, so a mage deals it fully
+        let mut mage = Mage::new(entity("Merlin", 10, 0, 4), 1, 0, 0);
+        mage.entity.weapon = Some(Weapon::new(material, 0, 0, None, false, None));
+        assert_eq!(mage.attack_damage(), 8 + 4);
+    }
+
+    #[test]
+    fn tanky_fight_ends_in_a_draw_at_the_round_cap() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 9);
+        game_rules.set_max_rounds(3);
+        game_rules.set_actions(ScriptedActions::new([0; 3]));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 500, 5, 5, None, 20), 1);
+        let mut monster = Monster::new(
+            Entity::new("Golem".into(), 500, 5, 5, None, 20),
+            vec![],
+            vec![],
+        );
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!((result.outcome, result.rounds), (FightOutcome::Draw, 3));
+        assert!(player.entity.life_points() > 0 && monster.entity.life_points() > 0);
+    }
+
+    #[test]
+    fn loot_drops_by_its_weight() {
+        let loot = vec![
+            LootEntry {
+                weight: 1,
+                drop: Loot::Strength(3),
+            },
+            LootEntry {
+                weight: 3,
+                drop: Loot::Dexterity(2),
+            },
+        ];
+        // The weights are 1 and 3, so a roll of 2 of 4 drops the second entry
+        let mut game_rules = rules([2, 1]);
+        assert_eq!(game_rules.dice.pick_weighted(&[usize::MAX, 1]), None);
+        let mut monster = Monster::new(entity("Orc", 10, 0, 0), vec![], loot);
+        assert!(matches!(
+            monster.roll_loot(&mut game_rules),
+            Some(Loot::Dexterity(2))
+        ));
+        let strength = monster.roll_loot(&mut game_rules).unwrap();
+        assert!(monster.roll_loot(&mut game_rules).is_none());
+
+        let mut hero = entity("Hero", 10, 0, 1);
+        hero.take_loot(strength, Proficiency::FULL);
+        assert_eq!(hero.strength(), 4);
+        hero.take_loot(Loot::Strength(usize::MAX), Proficiency::FULL);
+        assert_eq!(hero.strength(), usize::MAX);
+    }
+
+    #[test]
+    fn looted_weapons_are_compared_by_the_proficient_damage() {
+        let sword = Weapon::new(Material::Iron, 0, 0, None, false, None);
+        let staff = Weapon::new(Material::Wood, 10, 0, None, false, None);
+        assert!(staff.calc_damage() > sword.calc_damage());
+
+        // A fighter can't use the spell power of the staff, so it keeps the sword
+        let mut fighter = Fighter::new(entity("Hero", 10, 0, 1), 1);
+        fighter.entity.weapon = Some(sword.clone());
+        let proficiency = fighter.proficiency();
+        fighter
+            .entity
+            .take_loot(Loot::Weapon(staff.clone()), proficiency);
+        assert_eq!(fighter.entity.weapon(), Some(&sword));
+
+        // A mage takes the staff
+        let mut mage = Mage::new(entity("Merlin", 10, 0, 1), 1, 10, 5);
+        mage.entity.weapon = Some(sword);
+        let proficiency = mage.proficiency();
+        mage.entity
+            .take_loot(Loot::Weapon(staff.clone()), proficiency);
+        assert_eq!(mage.entity.weapon(), Some(&staff));
+    }
+
+    #[test]
+    fn accessors_read_the_entity() {
+        let weapon = Weapon::new(Material::Stone, 0, 0, None, false, None);
+        let weapon_dmg = weapon.calc_damage();
+        let hero = Entity::new("Hero".into(), 30, 7, 9, Some(weapon), 2);
+        assert_eq!(hero.name(), "Hero");
+        assert_eq!((hero.life_points(), hero.max_life_points()), (30, 30));
+        assert_eq!((hero.dexterity(), hero.strength()), (7, 9));
+        assert_eq!(hero.weapon().map(Weapon::calc_damage), Some(weapon_dmg));
+        assert_eq!(
+            (hero.total_damage_dealt(), hero.total_damage_taken()),
+            (0, 0)
+        );
+    }
+
+    /// Lets `player` flee from an orc in the first round and returns the count of heal options offered.
+    fn offered_heals<C: Combatant>(mut player: C, flee: usize) -> Vec<usize> {
+        let mut game_rules = rules([6, 6]);
+        let (recorder, offered) = Recorder::new([flee]);
+        game_rules.set_actions(recorder);
+        let mut monster = Monster::new(entity("Orc", 100, 5, 1), vec![], vec![]);
+        player.fight(&mut monster, &mut game_rules);
+        offered_count(&offered, Msg::Heal)
+    }
+
+    #[test]
+    fn heal_option_is_only_offered_if_able_to_heal() {
+        let fighter = Fighter::new(entity("Hero", 50, 6, 4), 1);
+        assert!(!fighter.can_heal());
+        assert_eq!(offered_heals(fighter, 2), [0]);
+        let mage = Mage::new(entity("Merlin", 50, 6, 4), 1, 0, 0);
+        assert_eq!(offered_heals(mage, 3), [1]);
+    }
+
+    #[test]
+    fn health_bar_fills_with_the_life_points() {
+        assert_eq!(render_healthbar(0, 100), "[----------] 0/100");
+        assert_eq!(render_healthbar(50, 100), "[#####-----] 50/100");
+        assert_eq!(render_healthbar(100, 100), "[##########] 100/100");
+        // Overheal and no max life points don't break the bar
+        assert_eq!(render_healthbar(120, 100), "[##########] 120/100");
+        assert_eq!(render_healthbar(0, 0), "[----------] 0/0");
+    }
+
+    #[test]
+    fn huge_stats_saturate_instead_of_overflowing() {
+        let material = Material::Custom {
+            name: "Star".into(),
+            modifier: usize::MAX,
+        };
+        let weapon = Weapon::new(material, usize::MAX, 0, None, true, None);
+        assert_eq!(weapon.calc_damage(), usize::MAX);
+        let mut fighter = Fighter::new(entity("Hero", 10, 0, usize::MAX), usize::MAX);
+        fighter.entity.weapon = Some(weapon);
+        assert_eq!(fighter.attack_damage(), usize::MAX);
+        let range = DamageRange::new(usize::MAX);
+        assert_eq!(range.max, usize::MAX);
+        assert!(range.min < range.max);
+
+        let mut monster = Monster::new(entity("Titan", 100, 0, usize::MAX), vec![50], vec![]);
+        monster.entity.apply_dmg(90);
+        monster.advance_phase();
+        assert_eq!(monster.attack_damage(), usize::MAX);
+    }
+
+    #[test]
+    fn enrage_strengthens_the_monster_while_it_loses_health() {
+        let mut monster = Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]);
+        let mut damages = vec![];
+        for dmg in [0, 60, 20] {
+            monster.entity.apply_dmg(dmg);
+            damages.push(monster.attack_damage());
+        }
+        assert_eq!(damages, [20, 25, 30]);
+        // Healing above the thresholds calms the monster down
+        monster.entity.heal(60);
+        assert_eq!(monster.attack_damage(), 20);
+    }
+
+    #[test]
+    fn fleeing_monster_flees_below_the_threshold() {
+        let mut player = Fighter::new(entity("Hero", 100, 0, 1), 1);
+        let mut monster =
+            Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]).with_ai(AiBehavior::Fleeing);
+
+        // Healthy, it attacks: dodge, damage, crit and counter roll
+        let mut game_rules = rules([1, 20, 2, 1]);
+        assert!(!monster.select_action(&mut player, &mut game_rules));
+        assert_eq!(player.entity().life_points(), 80);
+
+        // Below the threshold, it tries to flee
+        monster.entity.apply_dmg(80);
+        let mut game_rules = rules([6]);
+        assert!(monster.select_action(&mut player, &mut game_rules));
+        assert_eq!(
+            game_rules.log_entries().last().unwrap().action,
+            LogAction::Flee
+        );
+
+        // An aggressive monster keeps attacking, enraged
+        let mut monster = Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]);
+        monster.entity.apply_dmg(80);
+        assert!(!monster.select_action(&mut player, &mut rules([1, 30, 2, 1])));
+        assert_eq!(player.entity().life_points(), 50);
+    }
+
+    #[test]
+    fn wounded_monster_heals_itself_on_hard() {
+        let mut player = Fighter::new(entity("Hero", 100, 0, 1), 1);
+        let wounded = || {
+            let mut troll = Monster::new(entity("Troll", 100, 0, 4), vec![], vec![]);
+            troll.entity.apply_dmg(60);
+            troll
+        };
+
+        // Below the threshold it heals if the dice rolled 9 of 9
+        let mut troll = wounded();
+        let mut game_rules = GameRules::with_random(Difficulty::Hard, FixedSequence::new([9]));
+        assert!(!troll.select_action(&mut player, &mut game_rules));
+        assert_eq!(troll.entity.life_points(), 40 + MONSTER_HEAL_PERCENT);
+        assert_eq!(
+            game_rules.log_entries().last().unwrap().action,
+            LogAction::Heal
+        );
+
+        // Otherwise it attacks, enraged: Dodge, damage, crit and counter roll
+        let mut troll = wounded();
+        let mut game_rules =
+            GameRules::with_random(Difficulty::Hard, FixedSequence::new([8, 1, 5, 2, 1]));
+        troll.select_action(&mut player, &mut game_rules);
+        assert_eq!(troll.entity.life_points(), 40);
+        assert_eq!(player.entity.life_points(), 95);
+
+        // Below hard it never heals
+        let mut troll = wounded();
+        troll.select_action(&mut player, &mut rules([1, 5, 2, 1]));
+        assert_eq!(troll.entity.life_points(), 40);
+    }
+
+    #[test]
+    fn ai_behavior_is_read_from_the_config() {
+        let monster: Monster = serde_json::from_value(serde_json::json!({
+            "entity": { "name": "Goblin", "life_points": 10, "dexterity": 1, "strength": 1 },
+            "ai": "Fleeing"
+        }))
+        .unwrap();
+        assert_eq!(monster.ai(), AiBehavior::Fleeing);
+        let monster: Monster = serde_json::from_value(serde_json::json!({
+            "entity": { "name": "Goblin", "life_points": 10, "dexterity": 1, "strength": 1 }
+        }))
+        .unwrap();
+        assert_eq!(monster.ai(), AiBehavior::Aggressive);
+    }
+
+    #[test]
+    fn sheets_summarize_the_stats_in_one_line() {
+        let mut hero = entity("Hero", 50, 6, 4);
+        hero.weapon = Some(Weapon::new(Material::Iron, 0, 0, None, false, None));
+        hero.apply_dmg(10);
+        assert_eq!(
+            hero.to_string(),
+            "`Hero`: 40/50 Lebenspunkte, Stärke 4, Geschicklichkeit 6, Waffe: Iron (3 Schaden)"
+        );
+        let fighter = Fighter::new(entity("Hero", 50, 6, 4), 2);
+        assert_eq!(
+            fighter.to_string(),
+            "`Hero`: 50/50 Lebenspunkte, Stärke 4, Geschicklichkeit 6, keine Waffe, Ausdauer 2"
+        );
+        let mut mage = Mage::new(entity("Merlin", 30, 2, 1), 3, 0, DEFAULT_HEAL_COST);
+        mage.entity.weapon = Some(Weapon::new(
+            Material::Custom {
+                name: "Mithril".into(),
+                modifier: 8,
+            },
+            2,
+            0,
+            None,
+            false,
+            None,
+        ));
+        assert_eq!(
+            mage.to_string(),
+            "`Merlin`: 30/30 Lebenspunkte, Stärke 1, Geschicklichkeit 2, Waffe: Mithril (10 Schaden), Magiekraft 3, Mana 0/20"
+        );
+        let monster =
+            Monster::new(entity("Orc", 40, 4, 8), vec![], vec![]).with_ai(AiBehavior::Fleeing);
+        assert_eq!(
+            monster.to_string(),
+            "`Orc`: 40/40 Lebenspunkte, Stärke 8, Geschicklichkeit 4, keine Waffe, Verhalten Fleeing"
+        );
+    }
+
+    #[test]
+    fn attacks_in_a_row_build_a_combo() {
+        let mut game_rules = rules([3, 4, 3, 1, 3, 4, 3, 1, 3, 4, 3, 1, 3, 4, 3, 1, 3, 1, 3, 1]);
+        let mut player = Fighter::new(entity("Hero", 100, 0, 4), 1);
+        let mut monster = Monster::new(entity("Orc", 100, 0, 1), vec![], vec![]);
+        let mut damages = vec![];
+        for _ in 0..3 {
+            let life_points = monster.entity.life_points();
+            player.attack(&mut monster, &mut game_rules);
+            damages.push(life_points - monster.entity.life_points());
         }
+        assert_eq!(
+            damages,
+            [4, 4 + COMBO_DAMAGE_BONUS, 4 + 2 * COMBO_DAMAGE_BONUS]
+        );
+        assert_eq!(player.entity.combo, 3);
+
+        // Any other action resets the combo
+        player.defend(&mut game_rules);
+        assert_eq!(player.entity.combo, 0);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(player.entity.combo, 1);
+
+        // Getting hit too
+        monster.attack(&mut player, &mut game_rules);
+        assert_eq!(player.entity.combo, 0);
+    }
+
+    #[test]
+    fn healing_resets_the_combo() {
+        // Two attacks, then the heal chosen by the script
+        let mut game_rules = rules([3, 1, 3, 1, 3, 1, 3, 1]);
+        game_rules.set_actions(ScriptedActions::new([1]));
+        let mut mage = Mage::new(entity("Merlin", 100, 0, 1), 1, 10, DEFAULT_HEAL_COST);
+        let mut monster = Monster::new(entity("Orc", 100, 0, 1), vec![], vec![]);
+        mage.attack(&mut monster, &mut game_rules);
+        mage.attack(&mut monster, &mut game_rules);
+        assert_eq!(mage.entity.combo, 2);
+        mage.entity.apply_dmg(10);
+        assert!(!mage.select_action(&mut monster, &mut game_rules));
+        assert_eq!(mage.entity.combo, 0);
+    }
+
+    #[test]
+    fn materials_are_ordered_by_their_modifier() {
+        assert!(Material::Wood < Material::Diamond);
+        assert_eq!(
+            Material::all().collect::<Vec<_>>(),
+            [
+                Material::Wood,
+                Material::Stone,
+                Material::Iron,
+                Material::Gold,
+                Material::MagicOre,
+                Material::Diamond
+            ]
+        );
+        assert!(Material::all().is_sorted());
+        assert_eq!(Material::strongest(), Material::Diamond);
+
+        let mithril = Material::Custom {
+            name: "Mithril".into(),
+            modifier: 8,
+        };
+        assert!(mithril > Material::Diamond);
+        let copper = Material::Custom {
+            name: "Copper".into(),
+            modifier: 3,
+        };
+        // On the same modifier the predefined material comes first
+        assert!(copper > Material::Iron);
+        assert!(copper < Material::Gold);
+    }
+
+    #[test]
+    fn fighter_steals_life_with_every_hit() {
+        // Dodge, damage, no crit and no counter, twice
+        let mut game_rules = rules([3, 40, 3, 1, 3, 40, 3, 1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 10), 4);
+        let mut monster = Monster::new(entity("Orc", 500, 0, 1), vec![], vec![]);
+        player.entity.apply_dmg(20);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(
+            player.entity.life_points(),
+            30 + 40 * 4 * LIFESTEAL_PERCENT / 100
+        );
+
+        // Healing stops at the max life points
+        player.entity.heal(12);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(player.entity.life_points(), 50);
+
+        // A high endurance steals at most a small fraction of the damage (with a stun roll)
+        let mut game_rules = rules([3, 1000, 3, 1, 1]);
+        let mut player = Fighter::new(entity("Hero", 1000, 0, 1), 1000);
+        let mut monster = Monster::new(entity("Orc", 5000, 0, 1), vec![], vec![]);
+        player.entity.apply_dmg(900);
+        player.attack(&mut monster, &mut game_rules);
+        assert_eq!(monster.entity.life_points(), 4000);
+        assert_eq!(
+            player.entity.life_points(),
+            100 + 1000 * MAX_LIFESTEAL_PERCENT / 100
+        );
+
+        // Other classes don't steal life
+        let mut game_rules = rules([3, 10, 3, 1]);
+        let mut archer = Archer::new(entity("Robin", 50, 0, 10), 1);
+        archer.entity.apply_dmg(20);
+        archer.attack(&mut monster, &mut game_rules);
+        assert_eq!(archer.entity.life_points(), 30);
+    }
+
+    #[test]
+    fn defeat_at_the_same_time_is_a_draw() {
+        // Only the initiative is rolled, then both die of poison
+        let mut game_rules = rules([1]);
+        let mut player = Fighter::new(entity("Hero", 5, 0, 1), 1);
+        let mut monster = Monster::new(entity("Orc", 5, 0, 1), vec![], vec![]);
+        player.entity.poison(10);
+        monster.entity.poison(10);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(
+            result,
+            FightResult {
+                outcome: FightOutcome::Draw,
+                rounds: 1
+            }
+        );
+
+        // A single defeat still decides the fight
+        let mut game_rules = rules([1]);
+        let mut player = Fighter::new(entity("Hero", 50, 0, 1), 1);
+        let mut monster = Monster::new(entity("Orc", 5, 0, 1), vec![], vec![]);
+        player.entity.poison(10);
+        monster.entity.poison(10);
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert_eq!(result.outcome, FightOutcome::PlayerWon);
+    }
+
+    #[test]
+    fn monster_scales_with_the_level_of_the_player() {
+        let stats = |monster: &Monster| (monster.entity.life_points(), monster.entity.strength);
+        let mut monster = Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]);
+        monster.scale_to_level(1);
+        assert_eq!(stats(&monster), (100, 20));
+
+        let mut monster = Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]);
+        monster.scale_to_level(3);
+        assert_eq!(stats(&monster), (120, 24));
+
+        // The scaling is configurable
+        let mut monster =
+            Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]).with_level_scaling(50);
+        monster.scale_to_level(3);
+        assert_eq!(stats(&monster), (200, 40));
+        let mut monster =
+            Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]).with_level_scaling(0);
+        monster.scale_to_level(3);
+        assert_eq!(stats(&monster), (100, 20));
+
+        // An absurd level saturates instead of overflowing
+        let mut monster = Monster::new(entity("Orc", 100, 0, 20), vec![], vec![]);
+        monster.scale_to_level(usize::MAX);
+        assert_eq!(stats(&monster), (usize::MAX / 100, usize::MAX / 100));
+    }
+
+    #[test]
+    fn cloned_entity_differs_only_by_the_changes() {
+        let mut hero = entity("Hero", 50, 6, 4);
+        hero.weapon = Some(Weapon::new(Material::Iron, 0, 0, None, false, None));
+        let mut clone = hero.clone();
+        assert_eq!(clone, hero);
+        clone.apply_dmg(10);
+        assert_ne!(clone, hero);
+        hero.apply_dmg(10);
+        assert_eq!(clone, hero);
+
+        // A serialization round-trip keeps the whole state outside of a fight
+        let mut fighter = Fighter::new(entity("Hero", 50, 6, 4), 3);
+        fighter.entity.inventory = vec![Weapon::new(Material::Gold, 2, 1, Some(5), true, None)];
+        let json = serde_json::to_string(&fighter).unwrap();
+        assert_eq!(serde_json::from_str::<Fighter>(&json).unwrap(), fighter);
+    }
+
+    #[test]
+    fn dice_pool_changes_the_distribution() {
+        let throws = |pool: DicePool| {
+            let mut dice = Dice::with_seed(6, 5);
+            dice.pool = pool;
+            (0..600).filter(|_| dice.throw_dice()).count()
+        };
+        let single = throws(DicePool::default());
+        let advantage = throws(DicePool {
+            count: 2,
+            mode: PoolMode::Max,
+        });
+        let summed = throws(DicePool {
+            count: 2,
+            mode: PoolMode::Sum,
+        });
+        // Roughly 1/6, 11/36 and 1/36 of the throws
+        assert!((70..=130).contains(&single), "{single}");
+        assert!((150..=215).contains(&advantage), "{advantage}");
+        assert!((5..=35).contains(&summed), "{summed}");
+
+        // A pool of a single die rolls like a single die
+        let mut a = Dice::with_seed(6, 5);
+        let mut b = Dice::new_pool(6, 1);
+        b.rng = Box::new(SmallRng::seed_from_u64(5));
+        assert_eq!(
+            (0..20).map(|_| a.apply_dice_roll(60)).collect::<Vec<_>>(),
+            (0..20).map(|_| b.apply_dice_roll(60)).collect::<Vec<_>>()
+        );
+
+        // Summed dice are applied to the sum of their sides
+        let mut dice = Dice::with_random(6, FixedSequence::new([2, 3, 6, 6]));
+        dice.pool = Dice::new_pool(6, 2).pool;
+        assert_eq!(dice.apply_dice_roll(12), 5);
+        assert!(dice.throw_dice());
+        assert_eq!(dice.flee_probability(), 1.0 / 36.0);
+    }
+
+    #[test]
+    fn taunting_fighter_draws_the_attacks_from_a_mage() {
+        use crate::config::PlayerType;
+
+        // The mage defends and the fighter taunts (or defends), then the troll attacks:
+        // Dodge, damage, no crit and no counter
+        let fight = |fighter_action: usize| {
+            let mut game_rules = rules([1, 8, 2, 1]);
+            game_rules.set_max_rounds(1);
+            game_rules.set_actions(ScriptedActions::new([2, fighter_action]));
+            let mut party = [
+                PlayerType::Mage(Mage::new(
+                    entity("Merlin", 30, 0, 1),
+                    1,
+                    0,
+                    DEFAULT_HEAL_COST,
+                )),
+                PlayerType::Fighter(Fighter::new(entity("Hero", 30, 0, 1), 1)),
+            ];
+            let mut troll = Monster::new(entity("Troll", 60, 0, 8), vec![], vec![]);
+            fight_party(&mut party, &mut troll, &mut game_rules);
+            party.map(|member| member.entity().life_points())
+        };
+        assert_eq!(fight(2), [30, 22]);
+        // Without the taunt the first member is attacked, halved by defending
+        assert_eq!(fight(1), [26, 30]);
+    }
+
+    #[test]
+    fn overheal_becomes_a_shield_absorbing_damage() {
+        let mut game_rules = rules([]);
+        let mut mage = Mage::new(entity("Merlin", 30, 0, 1), 1, 10, DEFAULT_HEAL_COST);
+        mage.entity.apply_dmg(2);
+        assert!(mage.heal(&mut game_rules));
+        assert_eq!(mage.entity.life_points(), 30);
+        assert_eq!(mage.entity.shield(), DEFAULT_BASE_HEAL - 2);
+
+        // The shield absorbs the damage first
+        assert!(!mage.entity.apply_dmg(5));
+        assert_eq!(mage.entity.shield(), 0);
+        assert_eq!(
+            mage.entity.life_points(),
+            30 - (5 - (DEFAULT_BASE_HEAL - 2))
+        );
+        assert_eq!(mage.entity.total_damage_taken(), 2 + 2);
+
+        // A shield decays every round and is gone in the next fight
+        mage.entity.shield = 9;
+        mage.entity.begin_round(&mut game_rules);
+        assert_eq!(mage.entity.shield(), 9 / SHIELD_DECAY_DIVISOR);
+        mage.entity.begin_fight();
+        assert_eq!(mage.entity.shield(), 0);
     }
 }
@@ -0,0 +1,28 @@
+pub mod action;
+pub mod config;
+pub mod game;
+pub mod messages;
+pub mod observer;
+pub mod output;
+pub mod presets;
+pub mod random;
+pub mod replay;
+pub mod simulation;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default delay between the revealed characters in seconds.
+pub const TIME_BETWEEN: f64 = 0.025;
+
+/// The delay used for the whole session, stored as the bits of the `f64`.
+static DELAY: AtomicU64 = AtomicU64::new(TIME_BETWEEN.to_bits());
+
+/// Sets the delay between the revealed characters in seconds, e.g. `0.0` to skip the animation.
+pub fn set_delay(delay: f64) {
+    DELAY.store(delay.to_bits(), Ordering::Relaxed);
+}
+
+/// Gets the delay between the revealed characters in seconds, `TIME_BETWEEN` by default.
+pub fn delay() -> f64 {
+    f64::from_bits(DELAY.load(Ordering::Relaxed))
+}
@@ -1,109 +1,867 @@
-pub mod game;
-
 use std::{
-    env::args,
-    fs::File,
-    io::{BufReader, BufWriter},
-    path::PathBuf,
+    env::{args, var},
+    io,
+    path::{Path, PathBuf},
+    process::exit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use console_utils::{
-    input::{reveal, select, spinner, SpinnerType},
+    input::{select, spinner, SpinnerType},
     styled::{Color, StyledText},
 };
-use game::*;
-use serde::{Deserialize, Serialize};
-
-pub const TIME_BETWEEN: f64 = 0.025;
+use simple_fantasy_game::{
+    action::{ActionSource, AutoPlayer, TerminalActions},
+    config::{schema, Config, PlayerType, Players},
+    delay,
+    game::*,
+    messages::{msg, set_lang, text, Lang, Msg},
+    output::reveal,
+    replay::Replay,
+    set_delay,
+};
 
-/// The config struct holds general Config for Player and Enemy with saving/loading from a file
-#[derive(Debug, Default, Serialize, Deserialize)]
-struct Config {
-    player: PlayerType,
-    enemy: Monster,
+/// The command line arguments:
+/// ./simple-fantasy-game [PATH] [SEED] [--lang de|en] [--auto] [--stats] [--fast] [--record FILE]
+///     [--replay FILE] [--max-rounds N] [--save FILE] [--resume FILE] [--reroll-initiative]
+///     [--tie-break initiator|defender|reroll] [--schema] [--list-materials] [--validate FILE]
+///     [--verbose] [--difficulty easy|normal|hard|dynamic|N]
+struct Args {
+    path: Option<PathBuf>,
+    seed: Option<u64>,
+    lang: Option<Lang>,
+    /// Lets the `AutoPlayer` play instead of prompting the user.
+    auto: bool,
+    /// Prints the combat numbers of the config instead of fighting.
+    stats: bool,
+    /// Skips the typing animation.
+    fast: bool,
+    /// Saves a replay of the fights to this file.
+    record: Option<PathBuf>,
+    /// Re-runs the fights of this replay file.
+    replay: Option<PathBuf>,
+    /// Count of rounds after which a fight ends in a draw.
+    max_rounds: Option<usize>,
+    /// Saves the game to this file after every round.
+    save: Option<PathBuf>,
+    /// Resumes the game saved in this file (and keeps saving to it).
+    resume: Option<PathBuf>,
+    /// Rolls the fight order every round instead of once per fight.
+    reroll_initiative: bool,
+    /// Who attacks first if the rolled dexterity equals the one of the enemy.
+    tie_break: Option<TieBreak>,
+    /// Prints the JSON Schema of the config instead of playing.
+    schema: bool,
+    /// Prints every material with its damage modifier instead of playing.
+    list_materials: bool,
+    /// Checks this config and prints `OK` or the error instead of playing.
+    validate: Option<PathBuf>,
+    /// Reveals the raw dice rolls.
+    verbose: bool,
+    /// Skips the difficulty prompt.
+    difficulty: Option<Difficulty>,
 }
 
-impl Config {
-    pub fn _new() -> Self {
-        Self::default()
-    }
+/// Gets the value of a flag from the `args`, `expected` as the error if it's missing.
+fn flag_value(args: &mut impl Iterator<Item = String>, expected: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| expected.to_string())
+}
 
-    /// Loads the config from json file if it exists
-    pub fn load_from_file(path: &PathBuf) -> Config {
-        if path.exists() {
-            let file = File::open(path).unwrap();
-            let reader = BufReader::new(file);
-            let config: Self = serde_json::from_reader(reader).unwrap();
-            reveal(
-                &format!("Konfigurationsdatei geladen von: {:?}\n", path),
-                TIME_BETWEEN,
-            );
-            config
-        } else {
-            reveal(
-                &format!("Konfigurationsdatei erstellt bei: {:?}\n", path),
-                TIME_BETWEEN,
-            );
-            let config = Config::default();
-            Self::save_to_file(config, path).unwrap()
+impl Args {
+    /// Parses the arguments, without the program name.
+    /// Returns the error of a missing or invalid value of a flag, e.g. an unknown difficulty.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut positional = vec![];
+        let mut lang = None;
+        let mut auto = false;
+        let mut stats = false;
+        let mut fast = false;
+        let mut record = None;
+        let mut replay = None;
+        let mut max_rounds = None;
+        let mut save = None;
+        let mut resume = None;
+        let mut reroll_initiative = false;
+        let mut tie_break = None;
+        let mut schema = false;
+        let mut list_materials = false;
+        let mut validate = None;
+        let mut verbose = false;
+        let mut difficulty = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--lang" => {
+                    let value = flag_value(&mut args, "Expected a language: --lang de|en")?;
+                    lang = Some(value.parse()?);
+                }
+                "--auto" => auto = true,
+                "--stats" => stats = true,
+                "--fast" => fast = true,
+                "--reroll-initiative" => reroll_initiative = true,
+                "--schema" => schema = true,
+                "--list-materials" => list_materials = true,
+                "--verbose" => verbose = true,
+                "--tie-break" => {
+                    let value = flag_value(
+                        &mut args,
+                        "Expected a tie-break: --tie-break initiator|defender|reroll",
+                    )?;
+                    tie_break = Some(value.parse()?);
+                }
+                "--difficulty" => {
+                    let value = flag_value(
+                        &mut args,
+                        "Expected a difficulty: --difficulty easy|normal|hard|dynamic|N",
+                    )?;
+                    difficulty = Some(value.parse()?);
+                }
+                "--record" => {
+                    record = Some(PathBuf::from(flag_value(
+                        &mut args,
+                        "Expected a file: --record FILE",
+                    )?));
+                }
+                "--replay" => {
+                    replay = Some(PathBuf::from(flag_value(
+                        &mut args,
+                        "Expected a file: --replay FILE",
+                    )?));
+                }
+                "--save" => {
+                    save = Some(PathBuf::from(flag_value(
+                        &mut args,
+                        "Expected a file: --save FILE",
+                    )?));
+                }
+                "--validate" => {
+                    validate = Some(PathBuf::from(flag_value(
+                        &mut args,
+                        "Expected a file: --validate FILE",
+                    )?));
+                }
+                "--resume" => {
+                    resume = Some(PathBuf::from(flag_value(
+                        &mut args,
+                        "Expected a file: --resume FILE",
+                    )?));
+                }
+                "--max-rounds" => {
+                    let value = flag_value(&mut args, "Expected a count: --max-rounds N")?;
+                    max_rounds = Some(
+                        value
+                            .parse()
+                            .map_err(|_| "Expected the count to be a number".to_string())?,
+                    );
+                }
+                _ => positional.push(arg),
+            }
         }
+        let mut positional = positional.into_iter();
+        Ok(Self {
+            path: positional.next().map(PathBuf::from),
+            seed: positional
+                .next()
+                .map(|seed| {
+                    seed.parse()
+                        .map_err(|_| "Expected the seed to be a number".to_string())
+                })
+                .transpose()?,
+            lang,
+            auto,
+            stats,
+            fast,
+            record,
+            replay,
+            max_rounds,
+            save,
+            resume,
+            reroll_initiative,
+            tie_break,
+            schema,
+            list_materials,
+            validate,
+            verbose,
+            difficulty,
+        })
     }
 
-    /// Saves the current config to a json file
-    pub fn save_to_file(config: Config, path: &PathBuf) -> std::io::Result<Config> {
-        let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(writer, &config)?;
-        Ok(config)
+    /// Gets the path of the config, the usage message if it's missing.
+    fn config_path(&self) -> Result<&PathBuf, &'static str> {
+        self.path.as_ref().ok_or_else(|| text(Msg::Usage))
     }
 }
 
-/// The player type loaded from the file
-#[derive(Debug, Serialize, Deserialize)]
-enum PlayerType {
-    Fighter(Fighter),
-    Mage(Mage),
-}
+fn main() {
+    // An invalid argument is reported with the usage, as a missing config path
+    let args = Args::parse(args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}\n{}", text(Msg::Usage));
+        exit(1);
+    });
+
+    // The language flag takes precedence over the `SFG_LANG` env var
+    let lang = args
+        .lang
+        .or_else(|| var("SFG_LANG").ok().and_then(|lang| lang.parse().ok()))
+        .unwrap_or_default();
+    set_lang(lang);
 
-impl Default for PlayerType {
-    fn default() -> Self {
-        Self::Fighter(Fighter::default())
+    // The fast flag or the `SFG_FAST` env var skip the typing animation
+    if args.fast || var("SFG_FAST").is_ok() {
+        set_delay(0.0);
     }
-}
 
-fn main() {
+    // The schema doesn't need a config
+    if args.schema {
+        println!("{:#}", schema());
+        return;
+    }
+    if args.list_materials {
+        for line in material_lines() {
+            println!("{line}");
+        }
+        return;
+    }
+    // Validating neither fights nor asks for the difficulty
+    if let Some(path) = &args.validate {
+        match validation_line(path) {
+            Ok(line) => println!("{line}"),
+            Err(e) => {
+                eprintln!("{e}");
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    // Get the first argument: ./simple-fantasy-game [HERE]
+    let path = match args.config_path() {
+        Ok(path) => path.clone(),
+        Err(usage) => {
+            eprintln!("{usage}");
+            exit(1);
+        }
+    };
+
     // Coole intro Scene
     reveal(
-        &format!(
-            "{} Emulator von {}\n",
-            StyledText::new("Simple Fantasy Game").fg(Color::Magenta),
-            StyledText::new("Nils Wrenger").fg(Color::Red)
+        &msg(
+            Msg::Intro,
+            &[
+                &StyledText::new("Simple Fantasy Game").fg(Color::Magenta),
+                &StyledText::new("Nils Wrenger").fg(Color::Red),
+            ],
         ),
-        TIME_BETWEEN,
+        delay(),
     );
     spinner(1.5, SpinnerType::Dots);
 
-    // Get the first argument: ./simple-fantasy-game [HERE]
-    let path = PathBuf::from(
-        args()
-            .nth(1)
-            .expect("Expected a path parameter: ./simple-fantasy-game [HERE]"),
-    );
-    let mut config = Config::load_from_file(&path);
-
-    // Determine Difficulty by user input
-    let options = ["Easy", "Normal", "Hard"];
-    let i = select("Schwierigkeit auswählen (Pfeiltasten, Enter)", &options);
-    let mut game_rules = GameRules::new(Difficulty::from_i(i));
-
-    // Start fight
-    let monster = &mut config.enemy;
-    match &mut config.player {
-        PlayerType::Fighter(fighter) => {
-            fighter.fight(monster, &mut game_rules);
+    let mut config = match Config::load_from_file(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            exit(1);
+        }
+    };
+
+    if args.stats {
+        print_stats(&config);
+        return;
+    }
+
+    // A replay or a saved game has its own seed, difficulty and actions
+    let loaded = args.replay.as_ref().or(args.resume.as_ref());
+    let replay = loaded.map(|path| match Replay::load_from_file(path) {
+        Ok(replay) if replay.matches(&config) => replay,
+        Ok(_) => {
+            eprintln!("{}", text(Msg::ReplayConfigMismatch));
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", msg(Msg::ReplayIoError, &[&e]));
+            exit(1);
+        }
+    });
+
+    // Determine Difficulty by the argument or user input, normal when playing automatically
+    let difficulty = if let Some(replay) = &replay {
+        replay.settings.difficulty
+    } else if let Some(difficulty) = args.difficulty {
+        difficulty
+    } else if args.auto {
+        Difficulty::Normal
+    } else {
+        let options = ["Easy", "Normal", "Hard", "Custom", "Dynamic"];
+        let i = select(text(Msg::SelectDifficulty), &options);
+        Difficulty::from_i(i)
+    };
+
+    // Get the optional second argument: ./simple-fantasy-game [PATH] [HERE]
+    // Recording and saving (also on an interrupt) need a seed, so a random one is used if none is given
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut game_rules = match &replay {
+        // The saved choices are replayed without the typing animation, then the game goes on
+        Some(replay) if args.resume.is_some() => {
+            let then: Box<dyn ActionSource> = if args.auto {
+                Box::new(AutoPlayer::default())
+            } else {
+                Box::new(TerminalActions)
+            };
+            let delay = delay();
+            set_delay(0.0);
+            replay.resumed_game_rules(then, delay)
+        }
+        Some(replay) => replay.game_rules(),
+        None => GameRules::with_seed(difficulty, seed),
+    };
+    if args.auto && replay.is_none() {
+        game_rules.set_actions(AutoPlayer::default());
+    }
+    if let (Some(max_rounds), None) = (args.max_rounds, &replay) {
+        game_rules.set_max_rounds(max_rounds);
+    }
+    if args.reroll_initiative && replay.is_none() {
+        game_rules.set_reroll_initiative(true);
+    }
+    if let (Some(tie_break), None) = (args.tie_break, &replay) {
+        game_rules.set_tie_break(tie_break);
+    }
+    game_rules.set_verbose(args.verbose);
+    game_rules.set_config(&config);
+    // After a Ctrl-C the game can be saved at the end of the round to the save file, otherwise to `INTERRUPT_SAVE_FILE`
+    // The handler only sets the flag, as prompting from its own thread would race the prompts of the game
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let flag = interrupted.clone();
+    let handler = move || {
+        // A second Ctrl-C quits at once, e.g. while waiting at a prompt
+        if flag.swap(true, Ordering::SeqCst) {
+            exit(130);
         }
-        PlayerType::Mage(mage) => {
-            mage.fight(monster, &mut game_rules);
+    };
+    if let Err(e) = ctrlc::set_handler(handler) {
+        eprintln!("{e}");
+    }
+    let interrupt_path = args
+        .save
+        .clone()
+        .or(args.resume.clone())
+        .unwrap_or_else(|| PathBuf::from(INTERRUPT_SAVE_FILE));
+    game_rules.set_interrupt(interrupted, move |replay| {
+        on_interrupt(replay, &interrupt_path)
+    });
+    if let Some(path) = args.save.or(args.resume) {
+        game_rules.set_autosave(path);
+    }
+
+    // Start fights, with the monsters scaled by the difficulty
+    let monsters = config.enemy.monsters_mut();
+    for monster in monsters.iter_mut() {
+        monster.entity.scale_stats(difficulty.stat_modifier());
+    }
+    // The last monster is the template of the win streak, fresh as it was before the fights
+    let template = monsters.last().cloned();
+    let cleared = match &mut config.player {
+        Players::Single(player) => run_dungeon(&mut **player, monsters, &mut game_rules),
+        Players::Party(members) => run_party_dungeon(members, monsters, &mut game_rules),
+    };
+    if monsters.len() > 1 {
+        reveal(
+            &msg(Msg::DungeonCleared, &[&cleared, &monsters.len()]),
+            delay(),
+        );
+    }
+    if let (Players::Single(player), Some(template)) = (&mut config.player, template) {
+        run_streak(&mut **player, &template, &mut game_rules);
+    }
+
+    if let (Some(path), Some(replay)) = (args.record, Replay::record(&game_rules)) {
+        match replay.save_to_file(&path) {
+            Ok(()) => reveal(&msg(Msg::ReplaySaved, &[&format!("{path:?}")]), delay()),
+            Err(e) => eprintln!("{}", msg(Msg::ReplayIoError, &[&e])),
+        }
+    }
+}
+
+/// Gets `OK` if the config at `path` loads and passes every check, otherwise the error (see `Config::validate_file`).
+fn validation_line(path: &Path) -> Result<&'static str, String> {
+    Config::validate_file(path)
+        .map(|_| text(Msg::ConfigValid))
+        .map_err(|e| e.to_string())
+}
+
+/// File an interrupted game is saved to, if it isn't saved to a file with `--save` or `--resume` anyway.
+const INTERRUPT_SAVE_FILE: &str = "interrupted.json";
+
+/// Asks whether to save the game of the last round to `path` and quits, called at the end of the round after a Ctrl-C.
+fn on_interrupt(replay: Option<Replay>, path: &Path) {
+    let confirm = || select(text(Msg::InterruptPrompt), &[text(Msg::Yes), text(Msg::No)]) == 0;
+    match save_on_interrupt(replay, path, confirm) {
+        Ok(true) => reveal(&msg(Msg::InterruptSaved, &[&format!("{path:?}")]), delay()),
+        Ok(false) => {}
+        Err(e) => eprintln!("{}", msg(Msg::ReplayIoError, &[&e])),
+    }
+    exit(130);
+}
+
+/// Saves the `replay` of the interrupted game to `path` if `confirm` agrees, so it can be resumed.
+/// Nothing is saved (and `confirm` isn't asked) without a replay, as the dice isn't seeded.
+/// Returns true if the game was saved!
+fn save_on_interrupt(
+    replay: Option<Replay>,
+    path: &Path,
+    confirm: impl FnOnce() -> bool,
+) -> io::Result<bool> {
+    match replay {
+        Some(replay) if confirm() => replay.save_to_file(path).map(|()| true),
+        _ => Ok(false),
+    }
+}
+
+/// Reports the last error of saving the game after a round, see `GameRules::set_autosave`.
+fn report_autosave_error(game_rules: &mut GameRules) {
+    if let Some(e) = game_rules.take_autosave_error() {
+        eprintln!("{}", msg(Msg::ReplayIoError, &[&e]));
+    }
+}
+
+/// Fights the `monsters` one after another, carrying the remaining life points of the `player`
+/// between the fights. Stops when the player dies and returns the count of defeated monsters.
+fn run_dungeon<C: Combatant>(
+    player: &mut C,
+    monsters: &mut [Monster],
+    game_rules: &mut GameRules,
+) -> usize {
+    let mut cleared = 0;
+    for monster in monsters {
+        monster.scale_to_level(player.entity().level());
+        let result = player.fight(monster, game_rules);
+        report_autosave_error(game_rules);
+        reveal_summary(&summary_lines(result, &[player.entity()], &monster.entity));
+        match result.outcome {
+            FightOutcome::PlayerWon => {
+                cleared += 1;
+                if let Some(loot) = monster.roll_loot(game_rules) {
+                    let proficiency = player.proficiency();
+                    player.entity_mut().take_loot(loot, proficiency);
+                }
+            }
+            FightOutcome::EnemyWon => break,
+            FightOutcome::PlayerFled | FightOutcome::EnemyFled | FightOutcome::Draw => {}
         }
     }
+    cleared
+}
+
+/// Percent every monster of a win streak is stronger than the one before, see `run_streak`.
+const STREAK_SCALING_PERCENT: usize = 10;
+
+/// Asks whether to keep fighting after the dungeon, as long as the `player` is alive.
+/// Every accepted fight is against a fresh copy of the `template`, `STREAK_SCALING_PERCENT` stronger
+/// for every win of the streak. Stops when the player declines or dies and returns the win streak.
+fn run_streak<C: Combatant>(
+    player: &mut C,
+    template: &Monster,
+    game_rules: &mut GameRules,
+) -> usize {
+    let mut streak = 0;
+    while player.is_alive() && game_rules.confirm(text(Msg::ContinuePrompt)) {
+        let mut monster = template.clone();
+        monster
+            .entity
+            .scale_stats(100 + STREAK_SCALING_PERCENT * streak);
+        monster.scale_to_level(player.entity().level());
+        let result = player.fight(&mut monster, game_rules);
+        report_autosave_error(game_rules);
+        reveal_summary(&summary_lines(result, &[player.entity()], &monster.entity));
+        match result.outcome {
+            FightOutcome::PlayerWon => {
+                streak += 1;
+                reveal(&msg(Msg::WinStreak, &[&streak]), delay());
+                if let Some(loot) = monster.roll_loot(game_rules) {
+                    let proficiency = player.proficiency();
+                    player.entity_mut().take_loot(loot, proficiency);
+                }
+            }
+            FightOutcome::EnemyWon => break,
+            FightOutcome::PlayerFled | FightOutcome::EnemyFled | FightOutcome::Draw => {}
+        }
+    }
+    streak
+}
+
+/// Same as `run_dungeon` but for a party, see `fight_party`. Stops when every member is dead.
+fn run_party_dungeon(
+    members: &mut [PlayerType],
+    monsters: &mut [Monster],
+    game_rules: &mut GameRules,
+) -> usize {
+    let mut cleared = 0;
+    for monster in monsters {
+        // Scaled to the strongest member
+        let level = members.iter().map(|m| m.entity().level()).max();
+        monster.scale_to_level(level.unwrap_or(1));
+        let result = fight_party(members, monster, game_rules);
+        report_autosave_error(game_rules);
+        let players: Vec<&Entity> = members.iter().map(|m| m.entity()).collect();
+        reveal_summary(&summary_lines(result, &players, &monster.entity));
+        match result.outcome {
+            FightOutcome::PlayerWon => {
+                cleared += 1;
+                // The loot goes to the first member still alive
+                let member = members.iter_mut().find(|m| m.is_alive());
+                if let (Some(member), Some(loot)) = (member, monster.roll_loot(game_rules)) {
+                    let proficiency = member.proficiency();
+                    member.entity_mut().take_loot(loot, proficiency);
+                }
+            }
+            FightOutcome::EnemyWon => break,
+            FightOutcome::PlayerFled | FightOutcome::EnemyFled | FightOutcome::Draw => {}
+        }
+    }
+    cleared
+}
+
+/// Reveals the summary of the last fight, see `summary_lines`.
+fn reveal_summary(lines: &[String]) {
+    reveal(&format!("{}\n", lines.join("\n")), delay());
+}
+
+/// Gets the lines of the summary of a fight of the `players` against the `enemy` with the `result`:
+/// The rounds, how it ended and the remaining life points and dealt damage of every combatant.
+fn summary_lines(result: FightResult, players: &[&Entity], enemy: &Entity) -> Vec<String> {
+    let names = |entities: &[&Entity]| -> String {
+        let names: Vec<&str> = entities.iter().map(|e| e.name()).collect();
+        names.join("`, `")
+    };
+    // Dead members didn't flee, so the fleeing ones are the survivors
+    let survivors: Vec<&Entity> = players.iter().copied().filter(|p| p.is_alive()).collect();
+    let ending = match result.outcome {
+        FightOutcome::PlayerWon => msg(Msg::SummaryDefeated, &[&names(players), &enemy.name()]),
+        FightOutcome::EnemyWon => msg(Msg::SummaryDefeated, &[&enemy.name(), &names(players)]),
+        FightOutcome::PlayerFled => msg(Msg::SummaryFled, &[&names(&survivors)]),
+        FightOutcome::EnemyFled => msg(Msg::SummaryFled, &[&enemy.name()]),
+        FightOutcome::Draw => text(Msg::SummaryDraw).into(),
+    };
+    let mut lines = vec![msg(Msg::SummaryRounds, &[&result.rounds]), ending];
+    for entity in players.iter().chain([&enemy]) {
+        lines.push(msg(
+            Msg::SummaryEntity,
+            &[
+                &entity.name(),
+                &entity.life_points(),
+                &entity.total_damage_dealt(),
+            ],
+        ));
+    }
+    lines
+}
+
+/// Prints the combat numbers of the players and the monsters, see `stats_lines`.
+fn print_stats(config: &Config) {
+    for line in stats_lines(config) {
+        println!("{line}");
+    }
+}
+
+/// Gets a line for every predefined material with its damage modifier, from the weakest to the strongest.
+fn material_lines() -> Vec<String> {
+    Material::all()
+        .map(|material| {
+            msg(
+                Msg::MaterialModifier,
+                &[&material, &material.calc_modifier()],
+            )
+        })
+        .collect()
+}
+
+/// Gets the lines of the combat numbers of the players and the monsters, without any randomness.
+fn stats_lines(config: &Config) -> Vec<String> {
+    let mut lines = vec![];
+    for member in config.player.members() {
+        let player = member.entity();
+        lines.push(member.to_string());
+        lines.push(msg(
+            Msg::StatsAttack,
+            &[&player.name(), &DamageRange::new(member.attack_damage())],
+        ));
+        if let PlayerType::Mage(mage) = member {
+            lines.push(msg(Msg::StatsHeal, &[&player.name(), &mage.get_heal_lp()]));
+        }
+    }
+    for monster in config.enemy.monsters() {
+        lines.push(monster.to_string());
+        lines.push(msg(
+            Msg::StatsAttack,
+            &[
+                &monster.entity.name(),
+                &DamageRange::new(monster.attack_damage()),
+            ],
+        ));
+    }
+
+    for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+        let flee_chance = format!("{:.0}", difficulty.flee_chance() * 100.0);
+        lines.push(msg(
+            Msg::StatsDifficulty,
+            &[&format!("{difficulty:?}"), &flee_chance],
+        ));
+        for (player, monster) in config
+            .player
+            .members()
+            .iter()
+            .map(|m| m.entity())
+            .flat_map(|p| config.enemy.monsters().iter().map(move |m| (p, m)))
+        {
+            let chance = difficulty.first_attack_chance(
+                player.effective_dexterity(),
+                monster.entity.effective_dexterity(),
+            );
+            lines.push(msg(
+                Msg::StatsFirstAttack,
+                &[
+                    &player.name(),
+                    &format!("{:.0}", chance * 100.0),
+                    &monster.entity.name(),
+                ],
+            ));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    use simple_fantasy_game::{
+        action::ScriptedActions,
+        output::{set_sink, SilentSink},
+    };
+
+    /// Discards the output of the fights on the current thread, as the library is silent only in its own tests.
+    fn silence() {
+        set_sink(Box::new(SilentSink));
+    }
+
+    fn monster(name: &str, life_points: usize, dexterity: usize, strength: usize) -> Monster {
+        Monster::new(
+            Entity::new(name.into(), life_points, dexterity, strength, None, 0),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn dungeon_stops_at_the_death_of_the_player() {
+        silence();
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 2);
+        // The player kills the goblin with the first attack, then the orc attacks first and kills the player
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 10, 6, 4, None, 0), 1);
+        let mut monsters = [monster("Goblin", 4, 0, 1), monster("Orc", 100, 10, 20)];
+        assert_eq!(run_dungeon(&mut player, &mut monsters, &mut game_rules), 1);
+        assert_eq!(player.entity.life_points(), 0);
+        assert_eq!(monsters[0].entity.life_points(), 0);
+        assert_eq!(monsters[1].entity.life_points(), 100);
+    }
+
+    #[test]
+    fn interrupted_game_is_saved_if_confirmed() {
+        silence();
+        let path = std::env::temp_dir().join(format!(
+            "simple-fantasy-game-{}-interrupted.json",
+            std::process::id()
+        ));
+        // Without a seeded dice there is nothing to save
+        let unasked = || panic!("Expected no prompt without a replay");
+        assert!(!save_on_interrupt(None, &path, unasked).unwrap());
+
+        // The interrupt is handled at the end of the round on this thread, not while the round goes on
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let replays = Rc::new(RefCell::new(vec![]));
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 2);
+        let handled = replays.clone();
+        game_rules.set_interrupt(interrupted.clone(), move |replay| {
+            handled.borrow_mut().push(replay)
+        });
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 10, 6, 4, None, 0), 1);
+        player.fight(&mut monster("Goblin", 4, 0, 1), &mut game_rules);
+        assert!(replays.borrow().is_empty());
+        interrupted.store(true, Ordering::SeqCst);
+        game_rules.set_actions(ScriptedActions::new([0]));
+        player.fight(&mut monster("Goblin", 4, 0, 1), &mut game_rules);
+        assert_eq!(replays.borrow().len(), 1);
+        let replay = replays.borrow_mut().pop().flatten();
+
+        assert!(!save_on_interrupt(replay.clone(), &path, || false).unwrap());
+        assert!(!path.exists());
+        assert!(save_on_interrupt(replay, &path, || true).unwrap());
+        let saved = Replay::load_from_file(&path).unwrap();
+        assert_eq!((saved.seed, saved.choices), (2, vec![0, 0]));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn streak_goes_on_until_the_player_declines() {
+        silence();
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 2);
+        // Keep fighting and kill the goblin with one attack, twice, then decline
+        game_rules.set_actions(ScriptedActions::new([0, 0, 0, 0, 1]));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 100, 6, 4, None, 0), 1);
+        let goblin = monster("Goblin", 1, 0, 1);
+        assert_eq!(run_streak(&mut player, &goblin, &mut game_rules), 2);
+        assert!(player.is_alive());
+        // Fresh copies are fought, the template stays untouched
+        assert_eq!(goblin.entity.life_points(), 1);
+        assert_eq!(game_rules.choices(), [0, 0, 0, 0, 1]);
+
+        // A dead player isn't asked at all
+        game_rules.set_actions(ScriptedActions::new([]));
+        player.entity.apply_dmg(100);
+        assert_eq!(run_streak(&mut player, &goblin, &mut game_rules), 0);
+    }
+
+    #[test]
+    fn summary_shows_the_ending_and_every_combatant() {
+        let mut player = Fighter::new(Entity::new("Hero".into(), 30, 6, 4, None, 0), 1);
+        let mut orc = monster("Orc", 20, 0, 1);
+        player.entity.apply_dmg(5);
+        orc.entity.apply_dmg(20);
+        let result = FightResult {
+            outcome: FightOutcome::PlayerWon,
+            rounds: 3,
+        };
+        assert_eq!(
+            summary_lines(result, &[&player.entity], &orc.entity),
+            [
+                msg(Msg::SummaryRounds, &[&3]),
+                msg(Msg::SummaryDefeated, &[&"Hero", &"Orc"]),
+                msg(Msg::SummaryEntity, &[&"Hero", &25, &0]),
+                msg(Msg::SummaryEntity, &[&"Orc", &0, &0]),
+            ]
+        );
+
+        // Only the surviving members of a party fled
+        let mut mage = Fighter::new(Entity::new("Mage".into(), 10, 6, 4, None, 0), 1);
+        mage.entity.apply_dmg(10);
+        let result = FightResult {
+            outcome: FightOutcome::PlayerFled,
+            rounds: 7,
+        };
+        let lines = summary_lines(result, &[&player.entity, &mage.entity], &orc.entity);
+        assert_eq!(lines[1], msg(Msg::SummaryFled, &[&"Hero"]));
+        assert_eq!(lines.len(), 5);
+    }
+
+    #[test]
+    fn material_list_shows_every_modifier() {
+        assert_eq!(
+            material_lines(),
+            [
+                ("Wood", 1),
+                ("Stone", 2),
+                ("Iron", 3),
+                ("Gold", 4),
+                ("MagicOre", 5),
+                ("Diamond", 6),
+            ]
+            .map(|(name, modifier)| msg(Msg::MaterialModifier, &[&name, &modifier]))
+        );
+        let args = Args::parse(["--list-materials".to_string()]).unwrap();
+        assert!(args.list_materials);
+    }
+
+    #[test]
+    fn validation_prints_ok_or_the_error() {
+        let path = |name: &str| {
+            std::env::temp_dir().join(format!("simple-fantasy-game-{}-{name}", std::process::id()))
+        };
+        let config = |life_points: usize| {
+            serde_json::json!({
+                "player": { "Fighter": {
+                    "entity": { "name": "Hero", "life_points": life_points, "dexterity": 6, "strength": 4 },
+                    "endurance": 2
+                } },
+                "enemy": { "entity": { "name": "Orc", "life_points": 40, "dexterity": 4, "strength": 8 } }
+            })
+            .to_string()
+        };
+        let valid = path("valid.json");
+        std::fs::write(&valid, config(50)).unwrap();
+        assert_eq!(validation_line(&valid), Ok(text(Msg::ConfigValid)));
+
+        let dead = path("dead.json");
+        std::fs::write(&dead, config(0)).unwrap();
+        let reason = msg(Msg::ZeroLifePoints, &[&"Hero"]);
+        assert_eq!(
+            validation_line(&dead),
+            Err(msg(Msg::ConfigInvalid, &[&reason]))
+        );
+
+        // A missing config is an error instead of being created
+        let missing = path("missing.json");
+        assert!(validation_line(&missing).is_err());
+        assert!(!missing.exists());
+        std::fs::remove_file(&valid).unwrap();
+        std::fs::remove_file(&dead).unwrap();
+
+        let args = Args::parse(["--validate".to_string(), "config.json".to_string()]).unwrap();
+        assert_eq!(args.validate, Some(PathBuf::from("config.json")));
+        assert_eq!(args.path, None);
+    }
+
+    #[test]
+    fn missing_config_path_gives_the_usage() {
+        let args = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+        assert_eq!(args(&["--auto"]).config_path(), Err(text(Msg::Usage)));
+        assert_eq!(
+            args(&["--auto", "config.json"]).config_path(),
+            Ok(&PathBuf::from("config.json"))
+        );
+    }
+
+    #[test]
+    fn invalid_or_missing_values_are_errors() {
+        let args = |args: &[&str]| Args::parse(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(
+            args(&["--difficulty", "foo"]).err(),
+            "foo".parse::<Difficulty>().err()
+        );
+        assert!(args(&["--lang", "fr"]).is_err());
+        assert!(args(&["--tie-break", "coin"]).is_err());
+        assert!(args(&["--max-rounds", "many"]).is_err());
+        assert!(args(&["config.json", "seed"]).is_err());
+        assert!(args(&["config.json", "--replay"]).is_err());
+        assert!(args(&["--difficulty", "hard", "config.json", "7"]).is_ok());
+    }
+
+    #[test]
+    fn stats_show_the_attack_damage() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "player": { "Fighter": {
+                "entity": {
+                    "name": "Hero", "life_points": 50, "dexterity": 6, "strength": 4,
+                    "weapon": { "material": "Iron", "spell_power": 0 }
+                },
+                "endurance": 2
+            } },
+            "enemy": { "entity": { "name": "Orc", "life_points": 40, "dexterity": 4, "strength": 8 } }
+        }))
+        .unwrap();
+        let lines = stats_lines(&config);
+        assert_eq!(lines[0], config.player.members()[0].to_string());
+        assert!(lines.contains(&msg(Msg::StatsAttack, &[&"Hero", &DamageRange::new(14)])));
+        assert!(lines.contains(&msg(Msg::StatsAttack, &[&"Orc", &DamageRange::new(8)])));
+        // On `Easy` only the highest of the 3 sides rolls above the dexterity of the orc
+        let chance = msg(Msg::StatsFirstAttack, &[&"Hero", &"33", &"Orc"]);
+        assert!(lines.contains(&chance));
+        // The flee chance is a percentage, as in the flee option of the fight
+        assert!(lines.contains(&msg(Msg::StatsDifficulty, &[&"Hard", &"11"])));
+    }
 }
@@ -0,0 +1,684 @@
+use std::{
+    fmt::Display,
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+/// The language of all user-facing messages.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    De,
+    En,
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "de" => Ok(Self::De),
+            "en" => Ok(Self::En),
+            _ => Err(format!("Unknown language `{s}`, expected `de` or `en`")),
+        }
+    }
+}
+
+/// The language used for the whole session, German by default.
+static LANG: AtomicU8 = AtomicU8::new(Lang::De as u8);
+
+/// Sets the language for all following messages.
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// Gets the current language.
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::De,
+    }
+}
+
+/// Keys of all user-facing messages.
+///
+/// The texts may contain `{}` placeholders, which are filled in order by `msg`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    Intro,
+    Usage,
+    ConfigLoaded,
+    ConfigCreated,
+    ConfigIoError,
+    ConfigMalformed,
+    ConfigInvalid,
+    ConfigValid,
+    ConfigVersionTooNew,
+    NoPlayers,
+    NoEnemies,
+    NotAnObject,
+    ReplayIoError,
+    ReplaySaved,
+    InterruptPrompt,
+    InterruptSaved,
+    ReplayMismatch,
+    ReplayExhausted,
+    ReplayConfigMismatch,
+    ZeroLifePoints,
+    EmptyName,
+    StatTooHigh,
+    SelectDifficulty,
+    DiceSidesPrompt,
+    DifficultyAdapted,
+    NamePrompt,
+    PointBuyIntro,
+    StrengthPrompt,
+    DexterityPrompt,
+    LifePointsPrompt,
+    PointsOverBudget,
+    NoLifePointsBought,
+    DungeonCleared,
+    ContinuePrompt,
+    Yes,
+    No,
+    WinStreak,
+    SummaryRounds,
+    SummaryDefeated,
+    SummaryFled,
+    SummaryDraw,
+    SummaryEntity,
+    SheetEntity,
+    SheetWeapon,
+    SheetNoWeapon,
+    SheetFighter,
+    SheetMage,
+    SheetArcher,
+    SheetRogue,
+    SheetMonster,
+    StatsAttack,
+    StatsHeal,
+    StatsDifficulty,
+    StatsFirstAttack,
+    MaterialModifier,
+    Player,
+    Enemy,
+    FirstAttacker,
+    RoundStart,
+    RoundStatus,
+    SelectAction,
+    Attack,
+    AttackOption,
+    Heal,
+    HealOption,
+    Defend,
+    DefendOption,
+    Potion,
+    PotionOption,
+    PotionUsed,
+    Antidote,
+    AntidoteOption,
+    AntidoteUsed,
+    SwapWeapon,
+    SelectWeapon,
+    WeaponOption,
+    WeaponOptionLocked,
+    WeaponSwapped,
+    WeaponLocked,
+    NotProficient,
+    Taunt,
+    Taunted,
+    Flee,
+    Draw,
+    FleeOption,
+    FleeCooldownOption,
+    FleeSuccess,
+    FleeFailure,
+    CriticalHit,
+    Fumble,
+    FumbleHurt,
+    FumbleDefeated,
+    GuardBroken,
+    Combo,
+    Dodged,
+    Backstab,
+    Counter,
+    ExtraAttack,
+    Resisted,
+    Weakness,
+    AttackDefeated,
+    AttackHit,
+    Defends,
+    Healed,
+    Shielded,
+    NotEnoughMana,
+    Regenerated,
+    XpGained,
+    LevelUp,
+    Poisoned,
+    PoisonDamage,
+    PoisonDefeated,
+    Burning,
+    BurnDamage,
+    BurnDefeated,
+    Frozen,
+    Stunned,
+    StunSkipped,
+    DiceRoll,
+    LifeDrained,
+    Thorns,
+    ThornsDefeated,
+    WeaponBroken,
+    PhaseChange,
+    Enraged,
+    LootNothing,
+    LootWeapon,
+    LootWeaponDiscarded,
+    LootStrength,
+    LootDexterity,
+}
+
+impl Msg {
+    /// Gets the text of the message in `lang`.
+    pub fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::De => self.de(),
+            Lang::En => self.en(),
+        }
+    }
+
+    fn de(self) -> &'static str {
+        match self {
+            Self::Intro => "{} Emulator von {}\n",
+            Self::Usage => {
+                "Verwendung: ./simple-fantasy-game PFAD [SEED] [--lang de|en] [--auto] [--stats] [--fast] [--record DATEI] [--replay DATEI] [--max-rounds N] [--save DATEI] [--resume DATEI] [--reroll-initiative] [--tie-break initiator|defender|reroll] [--schema] [--list-materials] [--validate DATEI] [--verbose] [--difficulty easy|normal|hard|dynamic|N]"
+            }
+            Self::ConfigLoaded => "Konfigurationsdatei geladen von: {}\n",
+            Self::ConfigCreated => "Konfigurationsdatei erstellt bei: {}\n",
+            Self::ConfigIoError => {
+                "Konfigurationsdatei konnte nicht gelesen oder geschrieben werden: {}"
+            }
+            Self::ConfigMalformed => "Konfigurationsdatei ist fehlerhaft: {}",
+            Self::ConfigInvalid => "Konfigurationsdatei ist ungültig: {}",
+            Self::ConfigValid => "OK",
+            Self::ConfigVersionTooNew => {
+                "Konfigurationsdatei hat Version {}, unterstützt wird höchstens Version {}. Bitte das Spiel aktualisieren."
+            }
+            Self::NoPlayers => "Es gibt keine Spieler",
+            Self::NoEnemies => "Es gibt keine Gegner",
+            Self::NotAnObject => "Die Konfiguration ist kein Objekt",
+            Self::ReplayIoError => "Replay-Datei konnte nicht gelesen oder geschrieben werden: {}",
+            Self::ReplaySaved => "Replay gespeichert bei: {}\n",
+            Self::InterruptPrompt => "Spiel unterbrochen! Vor dem Beenden speichern?",
+            Self::InterruptSaved => "Spiel gespeichert bei: {}, fortsetzen mit `--resume`\n",
+            Self::ReplayMismatch => {
+                "Die Aufzeichnung passt nicht dazu: Aktion {} gewählt, aber es gibt nur {} Optionen"
+            }
+            Self::ReplayExhausted => "Die Aufzeichnung passt nicht dazu: Keine Aktionen mehr übrig",
+            Self::ReplayConfigMismatch => {
+                "Die Aufzeichnung passt nicht zur Konfiguration: Sie wurde mit anderen Kämpfern aufgezeichnet"
+            }
+            Self::ZeroLifePoints => "`{}` hat 0 Lebenspunkte",
+            Self::EmptyName => "Ein Name ist leer",
+            Self::StatTooHigh => "`{}` hat `{}` von {}, erlaubt sind höchstens {}",
+            Self::SelectDifficulty => "Schwierigkeit auswählen (Pfeiltasten, Enter)",
+            Self::DiceSidesPrompt => "Anzahl der Würfelseiten eingeben",
+            Self::DifficultyAdapted => {
+                "Die Schwierigkeit passt sich an: Der Würfel hat jetzt {} Seiten.\n"
+            }
+            Self::NamePrompt => "Namen des Spielers eingeben",
+            Self::PointBuyIntro => {
+                "Verteile {} Punkte auf Stärke, Geschicklichkeit und Lebenspunkte (je Punkt {} Lebenspunkte)!\n"
+            }
+            Self::StrengthPrompt => "Punkte für Stärke eingeben",
+            Self::DexterityPrompt => "Punkte für Geschicklichkeit eingeben",
+            Self::LifePointsPrompt => "Punkte für Lebenspunkte eingeben",
+            Self::PointsOverBudget => "{} Punkte verteilt, aber es gibt nur {} Punkte",
+            Self::NoLifePointsBought => {
+                "Mindestens ein Punkt muss auf Lebenspunkte verteilt werden"
+            }
+            Self::DungeonCleared => "{} von {} Gegnern besiegt!\n",
+            Self::ContinuePrompt => "Weiterkämpfen?",
+            Self::Yes => "Ja",
+            Self::No => "Nein",
+            Self::WinStreak => "Siegesserie: {}\n",
+            Self::SummaryRounds => "Zusammenfassung nach {} Runden:",
+            Self::SummaryDefeated => "  `{}` hat gewonnen, `{}` wurde besiegt!",
+            Self::SummaryFled => "  `{}` ist geflohen, es gibt keinen Sieger!",
+            Self::SummaryDraw => "  Unentschieden, es gibt keinen Sieger!",
+            Self::SummaryEntity => "  `{}`: {} Lebenspunkte übrig, {} Schaden verursacht",
+            Self::SheetEntity => "`{}`: {}/{} Lebenspunkte, Stärke {}, Geschicklichkeit {}, {}",
+            Self::SheetWeapon => "Waffe: {} ({} Schaden)",
+            Self::SheetNoWeapon => "keine Waffe",
+            Self::SheetFighter => "{}, Ausdauer {}",
+            Self::SheetMage => "{}, Magiekraft {}, Mana {}/{}",
+            Self::SheetArcher => "{}, Treffsicherheit {}",
+            Self::SheetRogue => "{}, Heimlichkeit {}",
+            Self::SheetMonster => "{}, Verhalten {}",
+            Self::StatsAttack => "`{}` verursacht {} Lebenspunkte Schaden pro Attacke",
+            Self::StatsHeal => "`{}` heilt {} Lebenspunkte",
+            Self::StatsDifficulty => "{}: Fluchtchance {}%",
+            Self::StatsFirstAttack => "  `{}` greift mit {}% Wahrscheinlichkeit vor `{}` an",
+            Self::MaterialModifier => "`{}`: {} Schaden",
+            Self::Player => "Spieler",
+            Self::Enemy => "Gegner",
+            Self::FirstAttacker => "{} wird zuerst angreifen!\n",
+            Self::RoundStart => "Runde {} hat begonnen!\n",
+            Self::RoundStatus => "`{}` hat {} Lebenspunkte und `{}` hat {} Lebenspunkte!\n",
+            Self::SelectAction => "Aktion auswählen (Pfeiltasten, Enter)",
+            Self::Attack => "Angreifen",
+            Self::AttackOption => "Angreifen ({} Lebenspunkte Schaden)",
+            Self::Heal => "Selber heilen",
+            Self::HealOption => "Selber heilen ({} Lebenspunkte, {}/{} Mana)",
+            Self::Defend => "Verteidigen",
+            Self::DefendOption => "Verteidigen (Halber Schaden beim nächsten Treffer)",
+            Self::Potion => "Trank benutzen",
+            Self::PotionOption => "Trank benutzen ({} Lebenspunkte, {} übrig)",
+            Self::PotionUsed => "`{}` hat einen Trank getrunken und {} Lebenspunkte geheilt!\n",
+            Self::Antidote => "Heiltrank gegen Gift benutzen",
+            Self::AntidoteOption => "Heiltrank gegen Gift benutzen ({} übrig)",
+            Self::AntidoteUsed => {
+                "`{}` hat einen Heiltrank gegen Gift getrunken und ist von allen Zuständen geheilt!\n"
+            }
+            Self::SwapWeapon => "Waffe wechseln",
+            Self::SelectWeapon => "Waffe auswählen (Pfeiltasten, Enter)",
+            Self::WeaponOption => "Waffe ({} Schaden)",
+            Self::WeaponOptionLocked => "Waffe ({} Schaden, erst ab Level {})",
+            Self::WeaponSwapped => "`{}` hat die Waffe gewechselt!\n",
+            Self::WeaponLocked => "`{}` kann {} erst ab Level {} führen!\n",
+            Self::NotProficient => "`{}` kann die Waffe nicht voll nutzen: {} statt {} Schaden!\n",
+            Self::Taunt => "Provozieren",
+            Self::Taunted => "`{}` zieht die Angriffe auf sich!\n",
+            Self::Flee => "Fliehen",
+            Self::Draw => "Unentschieden! Nach {} Runden wurde niemand besiegt!\n",
+            Self::FleeOption => "Fliehen ({}% Chance)",
+            Self::FleeCooldownOption => "Fliehen (Abklingzeit: {})",
+            Self::FleeSuccess => "Fliehen war erfolgreich!\n",
+            Self::FleeFailure => "Fliehen war nicht erfolgreich!\n",
+            Self::CriticalHit => "Kritischer Treffer!\n",
+            Self::Fumble => "Patzer! `{}` verfehlt völlig!\n",
+            Self::FumbleHurt => "`{}` verletzt sich selbst mit {} Schaden!\n",
+            Self::FumbleDefeated => "`{}` hat sich selbst besiegt!\n",
+            Self::GuardBroken => "Deckung durchbrochen!\n",
+            Self::Combo => "Combo x{}!\n",
+            Self::Dodged => "Ausgewichen! `{}` ist der Attacke von `{}` ausgewichen!\n",
+            Self::Backstab => "Hinterhalt! `{}` hat {} Extraschaden verursacht!\n",
+            Self::Counter => "`{}` kontert!\n",
+            Self::ExtraAttack => "`{}` greift gleich noch einmal an!\n",
+            Self::Resisted => "Material widerstanden! `{}` nimmt nur den halben Waffenschaden!\n",
+            Self::Weakness => "Schwachstelle! `{}` nimmt mehr Schaden!\n",
+            Self::AttackDefeated => "Attacke von `{}` hat `{}` besiegt!\n",
+            Self::AttackHit => "Attacke von `{}` hat mit einem Schaden von {} getroffen!\n",
+            Self::Defends => "`{}` verteidigt sich!\n",
+            Self::Healed => "`{}` hat sich mit {} Lebenspunkten geheilt!\n",
+            Self::Shielded => "`{}` erhält einen Schild von {} Lebenspunkten!\n",
+            Self::NotEnoughMana => "Nicht genug Mana!\n",
+            Self::Regenerated => "`{}` hat {} Lebenspunkte regeneriert!\n",
+            Self::XpGained => "`{}` hat {} Erfahrung erhalten!\n",
+            Self::LevelUp => "`{}` ist auf Level {} aufgestiegen!\n",
+            Self::Poisoned => "`{}` wurde vergiftet!\n",
+            Self::PoisonDamage => "`{}` erleidet {} Giftschaden!\n",
+            Self::PoisonDefeated => "`{}` wurde vom Gift besiegt!\n",
+            Self::Burning => "`{}` steht in Flammen!\n",
+            Self::BurnDamage => "`{}` erleidet {} Brandschaden!\n",
+            Self::BurnDefeated => "`{}` ist verbrannt!\n",
+            Self::Frozen => "`{}` ist gefroren und weicht schlechter aus!\n",
+            Self::Stunned => "`{}` wurde betäubt!\n",
+            Self::StunSkipped => "Betäubt! Runde von `{}` übersprungen!\n",
+            Self::DiceRoll => "Würfel: {}/{}\n",
+            Self::LifeDrained => "`{}` hat {} Lebenspunkte entzogen!\n",
+            Self::Thorns => "Die Dornen von `{}` verletzen `{}` mit {} Schaden!\n",
+            Self::ThornsDefeated => "Die Dornen von `{}` haben `{}` besiegt!\n",
+            Self::WeaponBroken => "Waffe von `{}` zerbrochen!\n",
+            Self::PhaseChange => "`{}` erreicht Phase {}!\n",
+            Self::Enraged => "Gegner ist wütend! `{}` schlägt härter zu!\n",
+            Self::LootNothing => "`{}` hat keine Beute gefunden!\n",
+            Self::LootWeapon => {
+                "`{}` hat eine neue Waffe mit {} Schaden gefunden und ausgerüstet!\n"
+            }
+            Self::LootWeaponDiscarded => {
+                "`{}` hat eine schwächere Waffe gefunden und liegen gelassen!\n"
+            }
+            Self::LootStrength => "`{}` hat {} Stärke erhalten!\n",
+            Self::LootDexterity => "`{}` hat {} Geschicklichkeit erhalten!\n",
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Self::Intro => "{} emulator by {}\n",
+            Self::Usage => {
+                "Usage: ./simple-fantasy-game PATH [SEED] [--lang de|en] [--auto] [--stats] [--fast] [--record FILE] [--replay FILE] [--max-rounds N] [--save FILE] [--resume FILE] [--reroll-initiative] [--tie-break initiator|defender|reroll] [--schema] [--list-materials] [--validate FILE] [--verbose] [--difficulty easy|normal|hard|dynamic|N]"
+            }
+            Self::ConfigLoaded => "Config file loaded from: {}\n",
+            Self::ConfigCreated => "Config file created at: {}\n",
+            Self::ConfigIoError => "Config file couldn't be read or written: {}",
+            Self::ConfigMalformed => "Config file is malformed: {}",
+            Self::ConfigInvalid => "Config file is invalid: {}",
+            Self::ConfigValid => "OK",
+            Self::ConfigVersionTooNew => {
+                "Config file has version {}, at most version {} is supported. Please update the game."
+            }
+            Self::NoPlayers => "There are no players",
+            Self::NoEnemies => "There are no enemies",
+            Self::NotAnObject => "The config is not an object",
+            Self::ReplayIoError => "Replay file couldn't be read or written: {}",
+            Self::ReplaySaved => "Replay saved at: {}\n",
+            Self::InterruptPrompt => "Game interrupted! Save before quitting?",
+            Self::InterruptSaved => "Game saved at: {}, continue with `--resume`\n",
+            Self::ReplayMismatch => {
+                "The recording doesn't match it: Chose action {}, but there are only {} options"
+            }
+            Self::ReplayExhausted => "The recording doesn't match it: No actions left",
+            Self::ReplayConfigMismatch => {
+                "The recording doesn't match the config: It was recorded with other combatants"
+            }
+            Self::ZeroLifePoints => "`{}` has 0 life points",
+            Self::EmptyName => "A name is empty",
+            Self::StatTooHigh => "`{}` has `{}` of {}, at most {} are allowed",
+            Self::SelectDifficulty => "Select difficulty (arrow keys, enter)",
+            Self::DiceSidesPrompt => "Enter the count of dice sides",
+            Self::DifficultyAdapted => "The difficulty adapts: The dice has {} sides now.\n",
+            Self::NamePrompt => "Enter the name of the player",
+            Self::PointBuyIntro => {
+                "Distribute {} points across strength, dexterity and life points ({} life points per point)!\n"
+            }
+            Self::StrengthPrompt => "Enter the points for strength",
+            Self::DexterityPrompt => "Enter the points for dexterity",
+            Self::LifePointsPrompt => "Enter the points for life points",
+            Self::PointsOverBudget => "Distributed {} points, but there are only {} points",
+            Self::NoLifePointsBought => "At least one point has to be spent on life points",
+            Self::DungeonCleared => "Defeated {} of {} enemies!\n",
+            Self::ContinuePrompt => "Keep fighting?",
+            Self::Yes => "Yes",
+            Self::No => "No",
+            Self::WinStreak => "Win streak: {}\n",
+            Self::SummaryRounds => "Summary after {} rounds:",
+            Self::SummaryDefeated => "  `{}` won, `{}` was defeated!",
+            Self::SummaryFled => "  `{}` fled, there is no winner!",
+            Self::SummaryDraw => "  Draw, there is no winner!",
+            Self::SummaryEntity => "  `{}`: {} life points left, {} damage dealt",
+            Self::SheetEntity => "`{}`: {}/{} life points, strength {}, dexterity {}, {}",
+            Self::SheetWeapon => "weapon: {} ({} damage)",
+            Self::SheetNoWeapon => "no weapon",
+            Self::SheetFighter => "{}, endurance {}",
+            Self::SheetMage => "{}, magic power {}, mana {}/{}",
+            Self::SheetArcher => "{}, accuracy {}",
+            Self::SheetRogue => "{}, stealth {}",
+            Self::SheetMonster => "{}, behavior {}",
+            Self::StatsAttack => "`{}` deals {} life points damage per attack",
+            Self::StatsHeal => "`{}` heals {} life points",
+            Self::StatsDifficulty => "{}: flee chance {}%",
+            Self::StatsFirstAttack => "  `{}` attacks with {}% probability before `{}`",
+            Self::MaterialModifier => "`{}`: {} damage",
+            Self::Player => "Player",
+            Self::Enemy => "Enemy",
+            Self::FirstAttacker => "{} attacks first!\n",
+            Self::RoundStart => "Round {} has begun!\n",
+            Self::RoundStatus => "`{}` has {} life points and `{}` has {} life points!\n",
+            Self::SelectAction => "Select action (arrow keys, enter)",
+            Self::Attack => "Attack",
+            Self::AttackOption => "Attack ({} life points damage)",
+            Self::Heal => "Heal yourself",
+            Self::HealOption => "Heal yourself ({} life points, {}/{} mana)",
+            Self::Defend => "Defend",
+            Self::DefendOption => "Defend (half damage on the next hit)",
+            Self::Potion => "Use potion",
+            Self::PotionOption => "Use potion ({} life points, {} left)",
+            Self::PotionUsed => "`{}` drank a potion and healed {} life points!\n",
+            Self::Antidote => "Use antidote",
+            Self::AntidoteOption => "Use antidote ({} left)",
+            Self::AntidoteUsed => "`{}` drank an antidote and is cured of every status!\n",
+            Self::SwapWeapon => "Swap weapon",
+            Self::SelectWeapon => "Select weapon (arrow keys, enter)",
+            Self::WeaponOption => "Weapon ({} damage)",
+            Self::WeaponOptionLocked => "Weapon ({} damage, not before level {})",
+            Self::WeaponSwapped => "`{}` swapped the weapon!\n",
+            Self::WeaponLocked => "`{}` can't wield {} before level {}!\n",
+            Self::NotProficient => "`{}` can't use the weapon fully: {} instead of {} damage!\n",
+            Self::Taunt => "Taunt",
+            Self::Taunted => "`{}` draws the attacks!\n",
+            Self::Flee => "Flee",
+            Self::Draw => "Draw! Nobody was defeated after {} rounds!\n",
+            Self::FleeOption => "Flee ({}% chance)",
+            Self::FleeCooldownOption => "Flee (cooldown: {})",
+            Self::FleeSuccess => "Fleeing was successful!\n",
+            Self::FleeFailure => "Fleeing was not successful!\n",
+            Self::CriticalHit => "Critical hit!\n",
+            Self::Fumble => "Fumble! `{}` misses completely!\n",
+            Self::FumbleHurt => "`{}` hurts themselves for {} damage!\n",
+            Self::FumbleDefeated => "`{}` defeated themselves!\n",
+            Self::GuardBroken => "Guard broken!\n",
+            Self::Combo => "Combo x{}!\n",
+            Self::Dodged => "Dodged! `{}` dodged the attack of `{}`!\n",
+            Self::Backstab => "Backstab! `{}` dealt {} extra damage!\n",
+            Self::Counter => "`{}` counterattacks!\n",
+            Self::ExtraAttack => "`{}` attacks again!\n",
+            Self::Resisted => "Material resisted! `{}` takes only half the weapon damage!\n",
+            Self::Weakness => "Weak spot! `{}` takes more damage!\n",
+            Self::AttackDefeated => "Attack of `{}` defeated `{}`!\n",
+            Self::AttackHit => "Attack of `{}` hit with {} damage!\n",
+            Self::Defends => "`{}` is defending!\n",
+            Self::Healed => "`{}` healed {} life points!\n",
+            Self::Shielded => "`{}` gains a shield of {} life points!\n",
+            Self::NotEnoughMana => "Not enough mana!\n",
+            Self::Regenerated => "`{}` regenerated {} life points!\n",
+            Self::XpGained => "`{}` gained {} experience!\n",
+            Self::LevelUp => "`{}` reached level {}!\n",
+            Self::Poisoned => "`{}` was poisoned!\n",
+            Self::PoisonDamage => "`{}` takes {} poison damage!\n",
+            Self::PoisonDefeated => "`{}` was defeated by the poison!\n",
+            Self::Burning => "`{}` is on fire!\n",
+            Self::BurnDamage => "`{}` takes {} burn damage!\n",
+            Self::BurnDefeated => "`{}` burned to death!\n",
+            Self::Frozen => "`{}` is frozen and dodges worse!\n",
+            Self::Stunned => "`{}` was stunned!\n",
+            Self::StunSkipped => "Stunned! Turn of `{}` skipped!\n",
+            Self::DiceRoll => "Dice: {}/{}\n",
+            Self::LifeDrained => "`{}` drained {} life points!\n",
+            Self::Thorns => "The thorns of `{}` hurt `{}` for {} damage!\n",
+            Self::ThornsDefeated => "The thorns of `{}` defeated `{}`!\n",
+            Self::WeaponBroken => "Weapon of `{}` broke!\n",
+            Self::PhaseChange => "`{}` enters phase {}!\n",
+            Self::Enraged => "Enemy is enraged! `{}` hits harder!\n",
+            Self::LootNothing => "`{}` found no loot!\n",
+            Self::LootWeapon => "`{}` found and equipped a new weapon with {} damage!\n",
+            Self::LootWeaponDiscarded => "`{}` found a weaker weapon and left it behind!\n",
+            Self::LootStrength => "`{}` gained {} strength!\n",
+            Self::LootDexterity => "`{}` gained {} dexterity!\n",
+        }
+    }
+}
+
+/// Gets the text of the message `key` in the current language.
+pub fn text(key: Msg) -> &'static str {
+    key.text(lang())
+}
+
+/// Gets the text of the message `key` in the current language,
+/// with the `{}` placeholders filled by `args` in order.
+pub fn msg(key: Msg, args: &[&dyn Display]) -> String {
+    let mut parts = text(key).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every key of `Msg` in order.
+    const ALL: [Msg; 130] = {
+        use Msg::*;
+        [
+            Intro,
+            Usage,
+            ConfigLoaded,
+            ConfigCreated,
+            ConfigIoError,
+            ConfigMalformed,
+            ConfigInvalid,
+            ConfigValid,
+            ConfigVersionTooNew,
+            NoPlayers,
+            NoEnemies,
+            NotAnObject,
+            ReplayIoError,
+            ReplaySaved,
+            InterruptPrompt,
+            InterruptSaved,
+            ReplayMismatch,
+            ReplayExhausted,
+            ReplayConfigMismatch,
+            ZeroLifePoints,
+            EmptyName,
+            StatTooHigh,
+            SelectDifficulty,
+            DiceSidesPrompt,
+            DifficultyAdapted,
+            NamePrompt,
+            PointBuyIntro,
+            StrengthPrompt,
+            DexterityPrompt,
+            LifePointsPrompt,
+            PointsOverBudget,
+            NoLifePointsBought,
+            DungeonCleared,
+            ContinuePrompt,
+            Yes,
+            No,
+            WinStreak,
+            SummaryRounds,
+            SummaryDefeated,
+            SummaryFled,
+            SummaryDraw,
+            SummaryEntity,
+            SheetEntity,
+            SheetWeapon,
+            SheetNoWeapon,
+            SheetFighter,
+            SheetMage,
+            SheetArcher,
+            SheetRogue,
+            SheetMonster,
+            StatsAttack,
+            StatsHeal,
+            StatsDifficulty,
+            StatsFirstAttack,
+            MaterialModifier,
+            Player,
+            Enemy,
+            FirstAttacker,
+            RoundStart,
+            RoundStatus,
+            SelectAction,
+            Attack,
+            AttackOption,
+            Heal,
+            HealOption,
+            Defend,
+            DefendOption,
+            Potion,
+            PotionOption,
+            PotionUsed,
+            Antidote,
+            AntidoteOption,
+            AntidoteUsed,
+            SwapWeapon,
+            SelectWeapon,
+            WeaponOption,
+            WeaponOptionLocked,
+            WeaponSwapped,
+            WeaponLocked,
+            NotProficient,
+            Taunt,
+            Taunted,
+            Flee,
+            Draw,
+            FleeOption,
+            FleeCooldownOption,
+            FleeSuccess,
+            FleeFailure,
+            CriticalHit,
+            Fumble,
+            FumbleHurt,
+            FumbleDefeated,
+            GuardBroken,
+            Combo,
+            Dodged,
+            Backstab,
+            Counter,
+            ExtraAttack,
+            Resisted,
+            Weakness,
+            AttackDefeated,
+            AttackHit,
+            Defends,
+            Healed,
+            Shielded,
+            NotEnoughMana,
+            Regenerated,
+            XpGained,
+            LevelUp,
+            Poisoned,
+            PoisonDamage,
+            PoisonDefeated,
+            Burning,
+            BurnDamage,
+            BurnDefeated,
+            Frozen,
+            Stunned,
+            StunSkipped,
+            DiceRoll,
+            LifeDrained,
+            Thorns,
+            ThornsDefeated,
+            WeaponBroken,
+            PhaseChange,
+            Enraged,
+            LootNothing,
+            LootWeapon,
+            LootWeaponDiscarded,
+            LootStrength,
+            LootDexterity,
+        ]
+    };
+
+    #[test]
+    fn every_key_has_an_english_text_with_the_same_placeholders() {
+        for key in ALL {
+            let (de, en) = (key.text(Lang::De), key.text(Lang::En));
+            assert!(!en.is_empty(), "{key:?} has no English text");
+            assert_eq!(
+                de.matches("{}").count(),
+                en.matches("{}").count(),
+                "{key:?} has other placeholders in English"
+            );
+        }
+    }
+
+    #[test]
+    fn msg_fills_the_placeholders_in_order() {
+        let text = msg(Msg::RoundStatus, &[&"Hero", &"1/2", &"Orc", &"3/4"]);
+        let filled: Vec<usize> = ["Hero", "1/2", "Orc", "3/4"]
+            .iter()
+            .map(|arg| text.find(arg).unwrap())
+            .collect();
+        assert!(filled.is_sorted());
+        assert!(!text.contains("{}"));
+
+        let text = msg(Msg::SummaryEntity, &[&"Hero", &1, &2]);
+        let filled: Vec<usize> = ["Hero", "1", "2"]
+            .iter()
+            .map(|arg| text.find(arg).unwrap())
+            .collect();
+        assert!(filled.is_sorted());
+        assert!(!text.contains("{}"));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::game::Entity;
+
+/// Everything which wants to observe a fight, e.g. for animations, logging or an AI.
+///
+/// Both methods default to doing nothing, see `NoObserver`.
+pub trait FightObserver {
+    /// Called at the start of every `round`, after the round-start effects (e.g. regeneration).
+    fn round_start(&mut self, _round: usize, _players: &[&Entity], _enemy: &Entity) {}
+
+    /// Called at the end of every `round`, also if the fight ended in it.
+    fn round_end(&mut self, _round: usize, _players: &[&Entity], _enemy: &Entity) {}
+}
+
+/// Observes nothing. This is the default.
+#[derive(Debug, Default)]
+pub struct NoObserver;
+
+impl FightObserver for NoObserver {}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::{
+        action::ScriptedActions,
+        game::{Combatant, Difficulty, Fighter, GameRules, Monster},
+    };
+
+    /// Records every call as `(true, round)` for a start and `(false, round)` for an end.
+    struct Recorder(Rc<RefCell<Vec<(bool, usize)>>>);
+
+    impl FightObserver for Recorder {
+        fn round_start(&mut self, round: usize, _players: &[&Entity], _enemy: &Entity) {
+            self.0.borrow_mut().push((true, round));
+        }
+
+        fn round_end(&mut self, round: usize, _players: &[&Entity], _enemy: &Entity) {
+            self.0.borrow_mut().push((false, round));
+        }
+    }
+
+    #[test]
+    fn observer_is_called_once_per_round() {
+        let calls = Rc::default();
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 9);
+        game_rules.set_max_rounds(3);
+        game_rules.set_actions(ScriptedActions::new([0; 3]));
+        game_rules.set_observer(Recorder(Rc::clone(&calls)));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 500, 5, 5, None, 20), 1);
+        let mut monster = Monster::new(
+            Entity::new("Golem".into(), 500, 5, 5, None, 20),
+            vec![],
+            vec![],
+        );
+        player.fight(&mut monster, &mut game_rules);
+        assert_eq!(
+            *calls.borrow(),
+            [
+                (true, 1),
+                (false, 1),
+                (true, 2),
+                (false, 2),
+                (true, 3),
+                (false, 3)
+            ]
+        );
+    }
+}
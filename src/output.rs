@@ -0,0 +1,110 @@
+use std::{cell::RefCell, env::var, rc::Rc};
+
+/// Everything the revealed texts can be written to, e.g. the terminal or a buffer.
+///
+/// Every text of a game goes through `reveal`, so the output can be redirected or suppressed.
+pub trait OutputSink {
+    /// Outputs `text`, with a typing animation of `delay` seconds per character if animated at all.
+    fn reveal(&mut self, text: &str, delay: f64);
+}
+
+/// Reveals the texts on the terminal with the typing animation. This is the default.
+#[derive(Debug, Default)]
+pub struct TerminalSink;
+
+impl OutputSink for TerminalSink {
+    fn reveal(&mut self, text: &str, delay: f64) {
+        console_utils::input::reveal(text, delay);
+    }
+}
+
+/// Discards every text, e.g. for headless simulations or CI.
+#[derive(Debug, Default)]
+pub struct SilentSink;
+
+impl OutputSink for SilentSink {
+    fn reveal(&mut self, _text: &str, _delay: f64) {}
+}
+
+/// Collects every text without the animation, e.g. to check the output in tests.
+///
+/// Clones share the same buffer, so a clone can be set as the sink while the original reads it.
+#[derive(Debug, Default, Clone)]
+pub struct BufferSink(Rc<RefCell<String>>);
+
+impl BufferSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets every text collected so far.
+    pub fn contents(&self) -> String {
+        self.0.borrow().clone()
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn reveal(&mut self, text: &str, _delay: f64) {
+        self.0.borrow_mut().push_str(text);
+    }
+}
+
+thread_local! {
+    /// The sink of the current thread, see `set_sink`.
+    static SINK: RefCell<Box<dyn OutputSink>> = RefCell::new(default_sink());
+}
+
+/// Gets the `SilentSink` if the `SFG_QUIET` env var is set (or in tests), otherwise the `TerminalSink`.
+fn default_sink() -> Box<dyn OutputSink> {
+    if cfg!(test) || var("SFG_QUIET").is_ok() {
+        Box::new(SilentSink)
+    } else {
+        Box::new(TerminalSink)
+    }
+}
+
+/// Replaces the sink of the current thread and returns the previous one, e.g. to restore it later.
+///
+/// The sink is set per thread, so fights on different threads (e.g. parallel tests) don't mix their output.
+pub fn set_sink(sink: Box<dyn OutputSink>) -> Box<dyn OutputSink> {
+    SINK.with(|current| current.replace(sink))
+}
+
+/// Reveals `text` on the sink of the current thread, see `OutputSink::reveal`.
+pub fn reveal(text: &str, delay: f64) {
+    SINK.with(|sink| sink.borrow_mut().reveal(text, delay));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        action::ScriptedActions,
+        game::{Combatant, Difficulty, Entity, Fighter, GameRules, Monster},
+        messages::{msg, Msg},
+        random::FixedSequence,
+    };
+
+    #[test]
+    fn buffer_sink_captures_the_texts_of_a_fight() {
+        let buffer = BufferSink::new();
+        let previous = set_sink(Box::new(buffer.clone()));
+
+        // The hero attacks first and defeats the orc: Initiative, dodge, damage and crit roll
+        let mut game_rules =
+            GameRules::with_random(Difficulty::Normal, FixedSequence::new([6, 1, 4, 2]));
+        game_rules.set_actions(ScriptedActions::new([0]));
+        let mut player = Fighter::new(Entity::new("Hero".into(), 10, 6, 4, None, 0), 1);
+        let mut monster = Monster::new(Entity::new("Orc".into(), 1, 0, 1, None, 0), vec![], vec![]);
+        player.fight(&mut monster, &mut game_rules);
+        set_sink(previous);
+
+        let output = buffer.contents();
+        assert!(output.contains(&msg(Msg::RoundStart, &[&1])));
+        assert!(output.contains(&msg(Msg::AttackDefeated, &[&"Hero", &"Orc"])));
+
+        // The restored sink doesn't write to the buffer anymore
+        reveal("Hidden", 0.0);
+        assert_eq!(buffer.contents(), output);
+    }
+}
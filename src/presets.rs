@@ -0,0 +1,102 @@
+use crate::game::{Enchantment, Material, Weapon};
+
+/// An `Iron` weapon for fighters.
+pub fn iron_sword() -> Weapon {
+    Weapon::builder().material(Material::Iron).build()
+}
+
+/// A two-handed `Stone` weapon, hitting harder but lowering the dexterity.
+pub fn stone_hammer() -> Weapon {
+    Weapon::builder()
+        .material(Material::Stone)
+        .two_handed()
+        .build()
+}
+
+/// A `Wood` weapon for rogues, poisoning on every hit.
+pub fn poison_dagger() -> Weapon {
+    Weapon::builder().material(Material::Wood).poison(2).build()
+}
+
+/// A `Gold` weapon burning on every hit, but breaking after 20 attacks.
+pub fn fire_blade() -> Weapon {
+    Weapon::builder()
+        .material(Material::Gold)
+        .durability(20)
+        .enchantment(Enchantment::Fire)
+        .build()
+}
+
+/// A `Diamond` weapon with spell power for mages.
+pub fn diamond_staff() -> Weapon {
+    Weapon::builder()
+        .material(Material::Diamond)
+        .spell_power(4)
+        .build()
+}
+
+/// Every preset weapon by its name, from the weakest to the strongest.
+pub fn all() -> [(&'static str, Weapon); 5] {
+    [
+        ("Poison Dagger", poison_dagger()),
+        ("Iron Sword", iron_sword()),
+        ("Fire Blade", fire_blade()),
+        ("Stone Hammer", stone_hammer()),
+        ("Diamond Staff", diamond_staff()),
+    ]
+}
+
+/// Gets the preset weapon by its `name` (ignoring the case), e.g. `"Iron Sword"`.
+pub fn by_name(name: &str) -> Option<Weapon> {
+    all()
+        .into_iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name.trim()))
+        .map(|(_, weapon)| weapon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_builds_the_same_weapon_as_new() {
+        let built = Weapon::builder()
+            .material(Material::Gold)
+            .spell_power(3)
+            .poison(1)
+            .durability(10)
+            .two_handed()
+            .enchantment(Enchantment::Frost)
+            .build();
+        let new = Weapon::new(
+            Material::Gold,
+            3,
+            1,
+            Some(10),
+            true,
+            Some(Enchantment::Frost),
+        );
+        assert_eq!(built, new);
+        // Unset fields are the defaults of the config
+        assert_eq!(
+            Weapon::builder().build(),
+            Weapon::new(Material::Wood, 0, 0, None, false, None)
+        );
+    }
+
+    #[test]
+    fn presets_have_the_expected_stats() {
+        let staff = by_name("diamond staff").unwrap();
+        assert_eq!(
+            staff,
+            Weapon::new(Material::Diamond, 4, 0, None, false, None)
+        );
+        assert_eq!(staff.calc_damage(), 6 + 4);
+        assert_eq!(by_name("Iron Sword").unwrap().calc_damage(), 3);
+        assert!(by_name("Excalibur").is_none());
+
+        // Ordered from the weakest to the strongest
+        let damages: Vec<_> = all().iter().map(|(_, w)| w.calc_damage()).collect();
+        assert!(damages.is_sorted(), "{damages:?}");
+    }
+}
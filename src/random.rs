@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+
+use rand::{rngs::SmallRng, Rng};
+
+/// Everything which is able to produce random numbers, e.g. for the dice.
+///
+/// This decouples the dice from the rng, so rolls can also be preset.
+pub trait RandomSource {
+    /// Returns a number between `low` and `high` (both inclusive).
+    fn gen_range(&mut self, low: usize, high: usize) -> usize;
+}
+
+/// The real rng. This is the default.
+impl RandomSource for SmallRng {
+    fn gen_range(&mut self, low: usize, high: usize) -> usize {
+        self.random_range(low..=high)
+    }
+}
+
+/// Returns a preset queue of values, one per roll.
+#[derive(Debug, Default)]
+pub struct FixedSequence {
+    values: VecDeque<usize>,
+}
+
+impl FixedSequence {
+    pub fn new(values: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+        }
+    }
+}
+
+impl RandomSource for FixedSequence {
+    /// Panics if the sequence ran out of values or the value is out of `low..=high`!
+    fn gen_range(&mut self, low: usize, high: usize) -> usize {
+        let value = self
+            .values
+            .pop_front()
+            .expect("Expected the sequence to have another value");
+        assert!(
+            (low..=high).contains(&value),
+            "Fixed value {value} is out of {low}..={high}"
+        );
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_sequence_returns_its_values_in_order() {
+        let mut rng = FixedSequence::new([2, 6, 1]);
+        let values: Vec<usize> = (0..3).map(|_| rng.gen_range(1, 6)).collect();
+        assert_eq!(values, [2, 6, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of 1..=6")]
+    fn fixed_sequence_rejects_values_out_of_range() {
+        FixedSequence::new([7]).gen_range(1, 6);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{ActionSource, ResumedActions, ScriptedActions},
+    config::Config,
+    game::{GameRules, RuleSettings},
+};
+
+/// Everything needed to re-run fights exactly: The seed of the dice, the difficulty and every chosen action.
+/// Also used as a save game, which is resumed by re-running the recorded choices.
+///
+/// The choices only fit the config the game started with, so it is recorded too (see `matches`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    /// Every chosen action index in order.
+    pub choices: Vec<usize>,
+    /// The difficulty, the other rules and the config, see `RuleSettings`.
+    #[serde(flatten)]
+    pub settings: RuleSettings,
+}
+
+impl Replay {
+    /// Records the `choices` of `game_rules`. Returns `None` if the dice isn't seeded.
+    pub fn record(game_rules: &GameRules) -> Option<Self> {
+        Some(Self {
+            seed: game_rules.seed()?,
+            choices: game_rules.choices().to_vec(),
+            settings: RuleSettings::of(game_rules),
+        })
+    }
+
+    /// Returns true if the replay was recorded with the `config` (or without any),
+    /// so the recorded choices are made in the same fights again.
+    /// The version of the config is ignored if none was recorded, as older replays don't have one.
+    pub fn matches(&self, config: &Config) -> bool {
+        self.settings.config.as_ref().is_none_or(|recorded| {
+            serde_json::to_value(config).is_ok_and(|mut config| {
+                if recorded.get("version").is_none() {
+                    if let Some(config) = config.as_object_mut() {
+                        config.remove("version");
+                    }
+                }
+                *recorded == config
+            })
+        })
+    }
+
+    /// Creates the game rules re-playing the recorded choices with the recorded seed.
+    pub fn game_rules(&self) -> GameRules {
+        let mut game_rules = self.settings.game_rules(Some(self.seed));
+        game_rules.set_actions(ScriptedActions::new(self.choices.iter().copied()));
+        game_rules
+    }
+
+    /// Same as `game_rules` but continues with `then` after the recorded choices,
+    /// resetting the reveal delay to `delay` (see `ResumedActions`).
+    pub fn resumed_game_rules(&self, then: Box<dyn ActionSource>, delay: f64) -> GameRules {
+        let mut game_rules = self.settings.game_rules(Some(self.seed));
+        game_rules.set_actions(ResumedActions::new(
+            self.choices.iter().copied(),
+            then,
+            delay,
+        ));
+        game_rules
+    }
+
+    /// Loads a replay from a json file.
+    pub fn load_from_file(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Saves the replay to a json file.
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        action::AutoPlayer,
+        game::{Combatant, Difficulty, Entity, Fighter, LogEntry, Monster, DEFAULT_MAX_ROUNDS},
+    };
+
+    /// Fights a fighter against an orc and returns the combat log.
+    fn fight(game_rules: &mut GameRules) -> Vec<LogEntry> {
+        let mut player = Fighter::new(Entity::new("Hero".into(), 60, 6, 6, None, 0), 2);
+        let mut monster =
+            Monster::new(Entity::new("Orc".into(), 80, 6, 9, None, 0), vec![], vec![]);
+        player.fight(&mut monster, game_rules);
+        game_rules.log_entries().to_vec()
+    }
+
+    #[test]
+    fn replay_fights_the_same() {
+        let mut game_rules = GameRules::with_seed(Difficulty::Hard, 3);
+        game_rules.set_actions(AutoPlayer::default());
+        let log = fight(&mut game_rules);
+        let replay = Replay::record(&game_rules).unwrap();
+        assert!(!replay.choices.is_empty());
+
+        let path = std::env::temp_dir().join(format!(
+            "simple-fantasy-game-{}-replay.json",
+            std::process::id()
+        ));
+        replay.save_to_file(&path).unwrap();
+        let loaded = Replay::load_from_file(&path).unwrap();
+        assert_eq!(fight(&mut loaded.game_rules()), log);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resumed_game_ends_like_the_uninterrupted_one() {
+        let new_fighters = || {
+            let player = Fighter::new(Entity::new("Hero".into(), 60, 6, 6, None, 0), 2);
+            let monster =
+                Monster::new(Entity::new("Orc".into(), 80, 6, 9, None, 0), vec![], vec![]);
+            (player, monster)
+        };
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 11);
+        game_rules.set_actions(AutoPlayer::default());
+        let (mut player, mut monster) = new_fighters();
+        let result = player.fight(&mut monster, &mut game_rules);
+        assert!(result.rounds > 2);
+
+        // Saved after the second round, by ending the fight there
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 11);
+        game_rules.set_actions(AutoPlayer::default());
+        game_rules.set_max_rounds(2);
+        let (mut player, mut monster) = new_fighters();
+        player.fight(&mut monster, &mut game_rules);
+        let mut saved = Replay::record(&game_rules).unwrap();
+        saved.settings.max_rounds = DEFAULT_MAX_ROUNDS;
+
+        let mut game_rules = saved.resumed_game_rules(Box::new(AutoPlayer::default()), 0.0);
+        let (mut player, mut monster) = new_fighters();
+        assert_eq!(player.fight(&mut monster, &mut game_rules), result);
+    }
+
+    #[test]
+    fn saves_only_resume_with_the_config_they_started_with() {
+        let config = |life_points: usize| -> Config {
+            serde_json::from_value(serde_json::json!({
+                "player": { "Fighter": {
+                    "entity": { "name": "Hero", "life_points": life_points, "dexterity": 6, "strength": 6 },
+                    "endurance": 2
+                } },
+                "enemy": { "entity": { "name": "Orc", "life_points": 80, "dexterity": 6, "strength": 9 } }
+            }))
+            .unwrap()
+        };
+        let mut game_rules = GameRules::with_seed(Difficulty::Normal, 5);
+        game_rules.set_config(&config(60));
+        let path = std::env::temp_dir().join(format!(
+            "simple-fantasy-game-{}-config.json",
+            std::process::id()
+        ));
+        Replay::record(&game_rules)
+            .unwrap()
+            .save_to_file(&path)
+            .unwrap();
+        let saved = Replay::load_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(saved.matches(&config(60)));
+        assert!(!saved.matches(&config(61)));
+        // The resumed game keeps the config for the next save
+        let resumed = saved.resumed_game_rules(Box::new(AutoPlayer::default()), 0.0);
+        assert_eq!(resumed.config(), game_rules.config());
+
+        // Without a recorded config every config is accepted
+        let unchecked = Replay::record(&GameRules::with_seed(Difficulty::Normal, 5)).unwrap();
+        assert!(unchecked.matches(&config(61)));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    action::ActionSource,
+    config::PlayerType,
+    game::{Combatant, Difficulty, FightOutcome, GameRules, Monster},
+    output::{set_sink, SilentSink},
+};
+
+/// The outcome of a simulated fight with the stats of the player, see `simulate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Simulation {
+    pub outcome: FightOutcome,
+    /// Count of rounds elapsed.
+    pub rounds: usize,
+    /// Life points of the player left after the fight.
+    pub life_points: usize,
+    /// Damage dealt by the player, without the overkill.
+    pub damage_dealt: usize,
+    /// Damage taken by the player.
+    pub damage_taken: usize,
+}
+
+/// Simulates a fight of the `player` against the `monster` without any terminal I/O, e.g. for balance tests.
+///
+/// Like in the game the monster is scaled by the `difficulty`, the dice is seeded with `seed`
+/// and the `policy` (e.g. an `AutoPlayer`) chooses the actions of the player.
+pub fn simulate(
+    mut player: PlayerType,
+    mut monster: Monster,
+    difficulty: Difficulty,
+    seed: u64,
+    policy: impl ActionSource + 'static,
+) -> Simulation {
+    let mut game_rules = GameRules::with_seed(difficulty, seed);
+    game_rules.set_actions(policy);
+    monster.entity.scale_stats(difficulty.stat_modifier());
+
+    let previous = set_sink(Box::new(SilentSink));
+    let result = player.fight(&mut monster, &mut game_rules);
+    set_sink(previous);
+
+    let entity = player.entity();
+    Simulation {
+        outcome: result.outcome,
+        rounds: result.rounds,
+        life_points: entity.life_points(),
+        damage_dealt: entity.total_damage_dealt(),
+        damage_taken: entity.total_damage_taken(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        action::AutoPlayer,
+        game::{Entity, Fighter},
+    };
+
+    fn hero() -> PlayerType {
+        PlayerType::Fighter(Fighter::new(
+            Entity::new("Hero".into(), 100, 6, 10, None, 2),
+            2,
+        ))
+    }
+
+    fn orc() -> Monster {
+        Monster::new(
+            Entity::new("Orc".into(), 100, 6, 10, None, 2),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn same_seed_simulates_the_same_fight() {
+        let a = simulate(hero(), orc(), Difficulty::Normal, 3, AutoPlayer::default());
+        let b = simulate(hero(), orc(), Difficulty::Normal, 3, AutoPlayer::default());
+        assert_eq!(a, b);
+        assert!(a.rounds > 0);
+    }
+
+    #[test]
+    fn win_rate_over_many_seeds_is_balanced() {
+        let wins = (0..100)
+            .map(|seed| {
+                simulate(
+                    hero(),
+                    orc(),
+                    Difficulty::Normal,
+                    seed,
+                    AutoPlayer::default(),
+                )
+            })
+            .filter(|simulation| simulation.outcome == FightOutcome::PlayerWon)
+            .count();
+        // Equal stats, but the endurance of the fighter gives the hero the edge
+        assert!((75..=95).contains(&wins), "{wins} wins");
+    }
+}